@@ -0,0 +1,85 @@
+//! Derive macro for [`internode`](https://crates.io/crates/internode). Use it through the `derive` feature of `internode`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Fields;
+use syn::Member;
+use syn::Type;
+
+/// Derives `Neighbors` from the fields annotated with `#[outgoing]` and `#[incoming]`.
+///
+/// Each of them may be annotated to at most one field, and the fields must be of the same type `F` where `&F` is an [`IntoIterator`] over `&Internode<Self>`, e.g. `Vec<Internode<Self>>`. If either is missing, the neighbors in that direction are always empty. Note that this doesn't make the graph undirected, which requires the incoming neighbors to mirror the outgoing ones as [`SymmetricNeighbors`](https://docs.rs/internode/latest/internode/trait.SymmetricNeighbors.html) does, and that whether a node is alive is determined by following edges in both directions, so a node may be dropped even if it's still referred to by nodes reachable only through the missing direction.
+#[proc_macro_derive(Neighbors, attributes(outgoing, incoming))]
+pub fn derive_neighbors(input: TokenStream) -> TokenStream {
+	let input = syn::parse_macro_input!(input as DeriveInput);
+	expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+	let shape_error =
+		|| Error::new(input.span(), "`Neighbors` can be derived only for structs with fields");
+	let Data::Struct(data) = &input.data else { return Err(shape_error()) };
+	let fields = match &data.fields {
+		Fields::Named(fields) => &fields.named,
+		Fields::Unnamed(fields) => &fields.unnamed,
+		Fields::Unit => return Err(shape_error()),
+	};
+	let mut outgoing = None::<(Member, &Type)>;
+	let mut incoming = None::<(Member, &Type)>;
+	for (index, field) in fields.iter().enumerate() {
+		let member = match &field.ident {
+			Some(ident) => Member::Named(ident.clone()),
+			None => Member::Unnamed(index.into()),
+		};
+		for attr in &field.attrs {
+			let slot = if attr.path().is_ident("outgoing") {
+				&mut outgoing
+			} else if attr.path().is_ident("incoming") {
+				&mut incoming
+			} else {
+				continue;
+			};
+			attr.meta.require_path_only()?;
+			if slot.replace((member.clone(), &field.ty)).is_some() {
+				return Err(Error::new(attr.span(), "duplicate attribute"));
+			}
+		}
+	}
+	let ty = match (&outgoing, &incoming) {
+		(Some((_, outgoing)), Some((_, incoming))) => {
+			if quote!(#outgoing).to_string() != quote!(#incoming).to_string() {
+				return Err(Error::new(
+					incoming.span(),
+					"`#[outgoing]` and `#[incoming]` fields must be of the same type",
+				));
+			}
+			outgoing
+		}
+		(Some((_, ty)), None) | (None, Some((_, ty))) => ty,
+		(None, None) => {
+			return Err(Error::new(input.span(), "no `#[outgoing]` or `#[incoming]` field"));
+		}
+	};
+	let neighbors = |field: &Option<(Member, &Type)>| match field {
+		Some((member, _)) => quote!(::std::option::Option::Some(&self.#member)),
+		None => quote!(::std::option::Option::None),
+	};
+	let (outgoing, incoming) = (neighbors(&outgoing), neighbors(&incoming));
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::internode::Neighbors for #name #ty_generics #where_clause {
+			type Iter<'__internode> = ::std::iter::Cloned<
+				::std::iter::Flatten<::std::option::IntoIter<&'__internode #ty>>,
+			>
+			where Self: '__internode;
+			fn outgoing(&self) -> Self::Iter<'_> { #outgoing.into_iter().flatten().cloned() }
+			fn incoming(&self) -> Self::Iter<'_> { #incoming.into_iter().flatten().cloned() }
+		}
+	})
+}
\ No newline at end of file
@@ -22,8 +22,6 @@ impl<T: Neighbors> Anchor<T> {
 impl<T: Neighbors> Drop for Anchor<T> {
 	fn drop(&mut self) {
 		drop(self.inner.anchor().lock().unwrap().take());
-		if !self.inner.should_live() {
-			self.inner.release()
-		}
+		self.inner.release_unless_anchored();
 	}
 }
\ No newline at end of file
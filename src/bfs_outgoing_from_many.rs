@@ -0,0 +1,28 @@
+use super::*;
+use genawaiter::sync::Gen;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+
+/// Performs a breadth-first search from all of `roots` at once by recursively calling [`Internode::outgoing_snapshot`], yielding each reachable node along with its distance from the nearest root and that root.
+///
+/// Every root is yielded first with distance `0` as its own nearest root. When a node is equally near to several roots, the one which comes earlier in `roots` wins.
+pub fn bfs_outgoing_from_many<T: Neighbors>(
+	roots: impl IntoIterator<Item = Internode<T>>,
+) -> impl FusedIterator<Item = (Internode<T>, usize, Internode<T>)> {
+	let search = roots.into_iter().map(|root| (root.clone(), 0, root)).collect::<VecDeque<_>>();
+	Gen::new(|co| async move {
+		let mut search = search;
+		let mut visited = HashSet::new();
+		while let Some((node, distance, root)) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				for neighbor in node.outgoing_snapshot().into_iter().flatten() {
+					search.push_back((neighbor, distance + 1, root.clone()));
+				}
+				co.yield_((node, distance, root)).await;
+			}
+		}
+	})
+	.into_iter()
+	.fuse()
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use super::*;
+use std::sync::atomic::Ordering;
+
+/// An `Internode` which memoizes snapshots of its neighbors.
+///
+/// Each node counts the changes to its value, which happen whenever the value is mutably dereferenced through a guard such as [`InternodeMutexGuard`], including edits by [`Internode::connect`] and the like, or when the node is dropped. The snapshots are reused without locking the node as long as the count is unchanged, so repeatedly reading the neighbors of a node whose edges rarely change costs only an atomic load. Note that the count is incremented even if the guard is mutably dereferenced only to edit other parts of the value than the neighbors.
+pub struct CachedInternode<T: Neighbors> {
+	inner: Internode<T>,
+	outgoing: Option<(u64, Option<Vec<Internode<T>>>)>,
+	incoming: Option<(u64, Option<Vec<Internode<T>>>)>,
+}
+
+impl<T: Neighbors> CachedInternode<T> {
+	/// Wraps the given `Internode` with empty caches.
+	pub fn new(inner: Internode<T>) -> Self { Self { inner, outgoing: None, incoming: None } }
+
+	/// Returns the wrapped `Internode`.
+	pub fn inner(&self) -> &Internode<T> { &self.inner }
+
+	/// Unwraps into the `Internode`, discarding the caches.
+	pub fn into_inner(self) -> Internode<T> { self.inner }
+
+	/// Same as [`Internode::outgoing_snapshot`], but reuses the previous snapshot if the value hasn't changed since then.
+	pub fn outgoing(&mut self) -> Option<&[Internode<T>]> {
+		Self::cached(&self.inner, &mut self.outgoing, Internode::outgoing_snapshot)
+	}
+
+	/// Same as [`Internode::incoming_snapshot`], but reuses the previous snapshot if the value hasn't changed since then.
+	pub fn incoming(&mut self) -> Option<&[Internode<T>]> {
+		Self::cached(&self.inner, &mut self.incoming, Internode::incoming_snapshot)
+	}
+
+	fn cached<'a>(
+		inner: &Internode<T>,
+		cache: &'a mut Option<(u64, Option<Vec<Internode<T>>>)>,
+		snapshot: impl FnOnce(&Internode<T>) -> Option<Vec<Internode<T>>>,
+	) -> Option<&'a [Internode<T>]> {
+		let generation = inner.generation().load(Ordering::Acquire);
+		if !matches!(cache, Some((cached, _)) if *cached == generation) {
+			// The count is read before taking the snapshot, so a change in between only causes another snapshot next time.
+			*cache = Some((generation, snapshot(inner)));
+		}
+		cache.as_ref().and_then(|(_, neighbors)| neighbors.as_deref())
+	}
+}
+
+impl<T: Neighbors> Clone for CachedInternode<T> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			outgoing: self.outgoing.clone(),
+			incoming: self.incoming.clone(),
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use super::*;
+use std::sync::Weak;
+
+/// A non-owning reference to the anchor of a [`Node`].
+///
+/// Unlike [`Internode`], this doesn't refer to a particular node, but to the anchor that a `Node` holds; it can be upgraded only while any other `Node` to the same node is held elsewhere, even if the connected graph itself is kept alive by `Node`s to other nodes. If all of them are dropped and then the node is anchored again, e.g. by [`Internode::upgrade`], this follows the new anchor. Neither keeps the node itself alive.
+///
+/// Returned by [`Node::into_handle`].
+pub struct ComponentHandle<T: Neighbors> {
+	anchor: Weak<Anchor<T>>,
+	node: Weak<InternodeImpl<T>>,
+}
+
+impl<T: Neighbors> ComponentHandle<T> {
+	pub(crate) fn new(anchor: Weak<Anchor<T>>, node: Weak<InternodeImpl<T>>) -> Self {
+		Self { anchor, node }
+	}
+
+	/// Tries to resurrect a `Node` sharing the anchor of the node this handle was created from.
+	pub fn upgrade(&self) -> Option<Node<T>> {
+		self.anchor
+			.upgrade()
+			.or_else(|| Internode::upgrade_weak(&self.node)?.anchor_upgraded())
+			.map(Node::from_anchor)
+	}
+}
+
+impl<T: Neighbors> Clone for ComponentHandle<T> {
+	fn clone(&self) -> Self {
+		Self { anchor: Weak::clone(&self.anchor), node: Weak::clone(&self.node) }
+	}
+}
\ No newline at end of file
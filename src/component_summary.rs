@@ -0,0 +1,19 @@
+/// A summary of the structure of nodes.
+///
+/// Returned by [`Internode::summary_outgoing`](crate::Internode::summary_outgoing).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentSummary {
+	/// The number of the nodes.
+	pub node_count: usize,
+	/// The number of the edges among the nodes.
+	pub edge_count: usize,
+	/// The number of the nodes having no incoming edge among the nodes.
+	pub source_count: usize,
+	/// The number of the nodes having no outgoing edge among the nodes.
+	pub sink_count: usize,
+	/// Whether there's a cycle among the nodes, including self-loops.
+	pub has_cycle: bool,
+	/// The maximum number of outgoing edges of a node among the nodes, or `0` if there's no node.
+	pub max_out_degree: usize,
+}
\ No newline at end of file
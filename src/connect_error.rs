@@ -0,0 +1,50 @@
+use super::*;
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+
+/// The reason why adding an edge fails.
+///
+/// Returned by [`Internode::try_connect`].
+pub enum ConnectError<T: Neighbors> {
+	/// The source node is reachable from the target node, so the edge would make a cycle.
+	WouldCycle { from: Internode<T>, to: Internode<T> },
+	/// Either the source or the target node is dropped already.
+	Dead { from: Internode<T>, to: Internode<T> },
+}
+
+impl<T: Neighbors> ConnectError<T> {
+	/// Returns the source node of the refused edge.
+	pub fn from(&self) -> &Internode<T> {
+		match self {
+			Self::WouldCycle { from, .. } | Self::Dead { from, .. } => from,
+		}
+	}
+
+	/// Returns the target node of the refused edge.
+	pub fn to(&self) -> &Internode<T> {
+		match self {
+			Self::WouldCycle { to, .. } | Self::Dead { to, .. } => to,
+		}
+	}
+}
+
+impl<T: Neighbors> Debug for ConnectError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::WouldCycle { .. } => f.debug_struct("WouldCycle").finish_non_exhaustive(),
+			Self::Dead { .. } => f.debug_struct("Dead").finish_non_exhaustive(),
+		}
+	}
+}
+
+impl<T: Neighbors> Display for ConnectError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::WouldCycle { .. } => write!(f, "edge would make a cycle"),
+			Self::Dead { .. } => write!(f, "node is dropped already"),
+		}
+	}
+}
+
+impl<T: Neighbors> Error for ConnectError<T> {}
\ No newline at end of file
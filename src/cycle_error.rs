@@ -0,0 +1,33 @@
+use super::*;
+use std::error::Error;
+use std::fmt::Debug;
+use std::fmt::Display;
+
+/// An error indicating that a cycle is found where an acyclic graph is expected.
+pub struct CycleError<T: Neighbors> {
+	node: Internode<T>,
+}
+
+impl<T: Neighbors> CycleError<T> {
+	pub(crate) fn new(node: Internode<T>) -> Self { Self { node } }
+
+	/// Returns a node on the cycle.
+	pub fn node(&self) -> &Internode<T> { &self.node }
+
+	/// Converts this error into the node on the cycle.
+	pub fn into_node(self) -> Internode<T> { self.node }
+}
+
+impl<T: Neighbors> Debug for CycleError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CycleError").finish_non_exhaustive()
+	}
+}
+
+impl<T: Neighbors> Display for CycleError<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "graph contains a cycle")
+	}
+}
+
+impl<T: Neighbors> Error for CycleError<T> {}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use super::*;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+/// A breadth-first search via [`Internode::outgoing`] which can be suspended and resumed, e.g. to paginate the nodes of a large graph.
+///
+/// The frontier and the visited nodes are kept across calls of [`Explorer::next_batch`], so no node is returned twice. Edges may be edited between batches, but as with [`Internode::bfs_outgoing`], edges added to nodes already expanded are missed, and nodes already returned are never returned again even if they're reached anew.
+///
+/// Returned by [`Internode::explorer_outgoing`].
+pub struct Explorer<T: Neighbors> {
+	search: VecDeque<Internode<T>>,
+	visited: HashSet<Internode<T>>,
+}
+
+impl<T: Neighbors> Explorer<T> {
+	pub(crate) fn new(start: Internode<T>) -> Self {
+		Self { search: VecDeque::from([start]), visited: HashSet::new() }
+	}
+
+	/// Expands the search until `n` more nodes are discovered or no node is left, and returns them in breadth-first order. Dropped nodes are skipped. Will be empty once the search is exhausted.
+	pub fn next_batch(&mut self, n: usize) -> Vec<Internode<T>> {
+		let mut batch = Vec::new();
+		while batch.len() < n {
+			let Some(node) = self.search.pop_front() else { break };
+			if self.visited.insert(node.clone()) {
+				if let Some(outgoing) = node.outgoing_snapshot() {
+					self.search.extend(outgoing);
+					batch.push(node);
+				}
+			}
+		}
+		batch
+	}
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use super::*;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+
+/// Hands out `Node`s deduplicated by value, so that equal values share a single node.
+///
+/// Note that this compares values by their [`Eq`] implementation, whereas [`Internode`] and [`Node`] compare by identity i.e. pointer. Two nodes created separately by [`Node::new`] are never equal even if their values are, but two `Node`s returned by [`Interner::intern`] for equal values are.
+///
+/// Only `Internode`s are held, so interning doesn't keep the nodes alive. Since the values stay behind the lock, modifying an interned value so that its hash or equality changes is a logic error like modifying a key of [`HashMap`]; the `Node` won't be found for either value afterwards.
+pub struct Interner<T: Neighbors, S = RandomState> {
+	buckets: HashMap<u64, Vec<Internode<T>>>,
+	hasher: S,
+}
+
+impl<T: Neighbors + Eq + Hash> Interner<T> {
+	/// Creates an empty `Interner`.
+	pub fn new() -> Self { Self::with_hasher(RandomState::new()) }
+}
+
+impl<T: Neighbors + Eq + Hash, S: BuildHasher> Interner<T, S> {
+	/// Creates an empty `Interner` which hashes values by the given hasher.
+	pub fn with_hasher(hasher: S) -> Self { Self { buckets: HashMap::new(), hasher } }
+
+	/// Returns a `Node` to an alive node whose value is equal to `value` if any, or creates a new `Node` with `value` otherwise.
+	///
+	/// This locks the interned nodes whose values have the same hash, one by one. Dropped nodes of the same hash are forgotten at the same time.
+	pub fn intern(&mut self, value: T) -> Node<T> {
+		let hash = self.hasher.hash_one(&value);
+		let bucket = self.buckets.entry(hash).or_default();
+		let mut found = None;
+		bucket.retain(|node| match node.lock() {
+			Some(guard) => {
+				if found.is_none() && *guard == value {
+					found = Some(node.clone());
+				}
+				true
+			}
+			None => false,
+		});
+		if let Some(node) = found {
+			return Node::from_internode(node);
+		}
+		let node = Node::new(value);
+		bucket.push(node.downgrade());
+		node
+	}
+
+	/// Forgets all the dropped nodes.
+	pub fn purge(&mut self) {
+		self.buckets.retain(|_, bucket| {
+			bucket.retain(|node| node.is_alive());
+			!bucket.is_empty()
+		});
+	}
+
+	/// Returns the number of interned nodes, including dropped ones not forgotten yet.
+	pub fn len(&self) -> usize { self.buckets.values().map(Vec::len).sum() }
+
+	/// Returns `true` if there's no interned node, including dropped ones not forgotten yet.
+	pub fn is_empty(&self) -> bool { self.buckets.is_empty() }
+}
+
+impl<T: Neighbors + Eq + Hash> Default for Interner<T> {
+	fn default() -> Self { Self::new() }
+}
\ No newline at end of file
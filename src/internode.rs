@@ -1,39 +1,225 @@
 use super::*;
 use genawaiter::sync::Gen;
+use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::hash::Hasher;
+use std::iter::FusedIterator;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::TryLockError;
 use std::sync::Weak;
+use std::time::Duration;
+use std::time::Instant;
 
-#[derive(Default)]
-struct InternodeImpl<T: Neighbors> {
-	value: Mutex<Option<T>>,
+/// Which neighbors to follow when traversing.
+#[derive(Clone, Copy)]
+pub(crate) enum Direction {
+	Outgoing,
+	Incoming,
+	Both,
+}
+
+impl Direction {
+	pub(crate) fn neighbors<T: Neighbors>(
+		self,
+		node: &Internode<T>,
+		value: &T,
+	) -> Vec<Internode<T>> {
+		match self {
+			Self::Outgoing => value.outgoing().collect(),
+			Self::Incoming => node.incoming_of(value).collect(),
+			Self::Both => value.outgoing().chain(node.incoming_of(value)).collect(),
+		}
+	}
+}
+
+pub(crate) struct InternodeImpl<T: Neighbors> {
+	value: ValueMutex<Option<T>>,
 	anchor: Mutex<Option<Weak<Anchor<T>>>>,
+	// Incoming neighbors recorded by `Internode::connect_indexed`, which `T` doesn't hold itself.
+	predecessors: Mutex<Vec<Weak<InternodeImpl<T>>>>,
+	generation: AtomicU64,
+	id: u64,
+}
+
+impl<T: Neighbors> InternodeImpl<T> {
+	fn new(value: Option<T>) -> Self {
+		static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+		Self {
+			value: ValueMutex::new(value),
+			anchor: Mutex::new(None),
+			predecessors: Mutex::new(Vec::new()),
+			generation: AtomicU64::new(0),
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+		}
+	}
+}
+
+impl<T: Neighbors> Default for InternodeImpl<T> {
+	fn default() -> Self { Self::new(None) }
 }
 
 /// A non-owning shared reference to a node.
 ///
+/// Traversals such as [`Internode::dfs_outgoing`] lock each node only while collecting its neighbors, so edges may be edited concurrently from other threads. In that case, edits to a node made after it's expanded are not reflected, but each node is still yielded at most once.
+///
+/// Implements [`Ord`] by the order of creation, so that it can be used in ordered collections like [`BTreeMap`]. The order is stable within the process, and unrelated to the values or the structure.
+///
 /// Returned by [`Node::downgrade`].
 #[derive(Default)]
 pub struct Internode<T: Neighbors>(Arc<InternodeImpl<T>>);
 
 impl<T: Neighbors> Internode<T> {
-	pub(crate) fn value(&self) -> &Mutex<Option<T>> { &self.0.value }
+	pub(crate) fn value(&self) -> &ValueMutex<Option<T>> { &self.0.value }
 
 	pub(crate) fn anchor(&self) -> &Mutex<Option<Weak<Anchor<T>>>> { &self.0.anchor }
 
-	pub(crate) fn new(value: T) -> Self {
-		Self(Arc::new(InternodeImpl { value: Mutex::new(Some(value)), anchor: Mutex::new(None) }))
+	/// Counts the changes to the value, which is incremented whenever the value may be mutated.
+	pub(crate) fn generation(&self) -> &AtomicU64 { &self.0.generation }
+
+	/// Takes the value out, making this `Internode` dropped.
+	pub(crate) fn take_value(&self) -> Option<T> {
+		let mut guard = self.value().lock().unwrap();
+		self.generation().fetch_add(1, Ordering::Release);
+		self.0.predecessors.lock().unwrap().clear();
+		guard.take()
+	}
+
+	/// Collects the incoming neighbors recorded by [`Internode::connect_indexed`].
+	pub(crate) fn predecessors(&self) -> Vec<Self> {
+		let mut predecessors = self.0.predecessors.lock().unwrap();
+		predecessors.retain(|weak| weak.strong_count() > 0);
+		predecessors.iter().filter_map(Self::upgrade_weak).collect()
+	}
+
+	/// Chains [`Neighbors::incoming`] of `value`, which must be the value of this node, with the incoming neighbors recorded by [`Internode::connect_indexed`].
+	pub(crate) fn incoming_of<'a>(&self, value: &'a T) -> impl 'a + Iterator<Item = Self> {
+		value.incoming().chain(self.predecessors())
 	}
 
+	pub(crate) fn new(value: T) -> Self { Self::from_value(Some(value)) }
+
+	pub(crate) fn from_value(value: Option<T>) -> Self { Self(Arc::new(InternodeImpl::new(value))) }
+
 	/// Blocks until the internal `Mutex` can be locked and returns a guard to the value. Will be `None` if this `Internode` is dropped already.
 	pub fn lock(&self) -> Option<InternodeMutexGuard<'_, T>> {
 		let guard = self.value().lock().unwrap();
-		guard.is_some().then(|| InternodeMutexGuard::new(guard))
+		guard.is_some().then(|| InternodeMutexGuard::new(guard, self.generation()))
+	}
+
+	/// Blocks until the internal `Mutex` can be locked, and replaces the value with the first of what `f` returns given the old value by value, returning the second. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The lock is held while `f` is running, so `f` shouldn't lock this node. Note that `f` is responsible for carrying over the neighbors to the new value, and the value is lost if `f` panics.
+	pub fn update<R>(&self, f: impl FnOnce(T) -> (T, R)) -> Option<R> {
+		let mut guard = self.value().lock().unwrap();
+		let (value, result) = f(guard.take()?);
+		self.generation().fetch_add(1, Ordering::Release);
+		*guard = Some(value);
+		Some(result)
+	}
+
+	/// Same as [`Internode::lock`], but the returned guard checks whether any edge to a neighbor is removed when it's dropped. If so, this node and such neighbors are dropped unless they're still connected to any anchored node.
+	///
+	/// Otherwise, nodes disconnected by edits through the guard are kept until the connected graph is dropped. Note that the check costs collecting the neighbors both on lock and on drop, and a traversal of the connected graph for each removed neighbor.
+	pub fn lock_tracked(&self) -> Option<TrackedInternodeMutexGuard<'_, T>> {
+		Some(TrackedInternodeMutexGuard::new(self, self.lock()?))
+	}
+
+	/// Tries to lock the internal `Mutex` until `timeout_ms` milliseconds elapse, and returns a guard to the value or the reason of failure.
+	///
+	/// Unlike [`Internode::lock`], this doesn't panic if the `Mutex` is poisoned.
+	pub fn lock_timeout_ms(
+		&self,
+		timeout_ms: u64,
+	) -> Result<InternodeMutexGuard<'_, T>, LockStatus> {
+		match self.value().try_lock_for(Duration::from_millis(timeout_ms)) {
+			Ok(guard) if guard.is_some() => Ok(InternodeMutexGuard::new(guard, self.generation())),
+			Ok(_) => Err(LockStatus::Dead),
+			Err(TryLockError::Poisoned(_)) => Err(LockStatus::Poisoned),
+			Err(TryLockError::WouldBlock) => Err(LockStatus::TimedOut),
+		}
+	}
+
+	/// Locks both this node and `other` in the order of their addresses, so that it doesn't deadlock with other threads locking the same pair. The second guard is `None` if both are the same node. Will be `None` if either is dropped already.
+	pub(crate) fn lock_both<'a>(
+		&'a self,
+		other: &'a Self,
+	) -> Option<(InternodeMutexGuard<'a, T>, Option<InternodeMutexGuard<'a, T>>)> {
+		if self == other {
+			Some((self.lock()?, None))
+		} else if Arc::as_ptr(&self.0) < Arc::as_ptr(&other.0) {
+			let guard = self.lock()?;
+			Some((guard, Some(other.lock()?)))
+		} else {
+			let guard_other = other.lock()?;
+			Some((self.lock()?, Some(guard_other)))
+		}
+	}
+
+	/// Locks both this node and `other` without deadlocking with other threads locking the same pair, and calls `f` with their values. Will be `None` if either is dropped already.
+	///
+	/// If both are the same node, it's locked only once and `f` receives the same value twice.
+	pub fn with_both<R>(&self, other: &Self, f: impl FnOnce(&T, &T) -> R) -> Option<R> {
+		let (guard, guard_other) = self.lock_both(other)?;
+		Some(f(&guard, guard_other.as_deref().unwrap_or(&guard)))
+	}
+
+	/// Collects the live nodes reachable via [`Internode::outgoing`] into `nodes`, replacing its contents, and locks all of them at once in the order of their addresses so that it doesn't deadlock with other threads doing the same. Returns the guards in the same order as `nodes`. This gives a consistent view of the nodes, as none of them can be edited until the guards are dropped.
+	///
+	/// Note that the nodes are collected before being locked, so a node which becomes reachable in between isn't locked, and a node which is dropped in between is skipped, leaving fewer guards than `nodes`.
+	pub fn lock_component_outgoing<'a>(
+		&self,
+		nodes: &'a mut Vec<Self>,
+	) -> Vec<InternodeMutexGuard<'a, T>> {
+		nodes.clear();
+		self.visit(Direction::Outgoing, |node, _| nodes.push(node.clone()));
+		nodes.sort_by_key(|node| Arc::as_ptr(&node.0));
+		nodes.iter().filter_map(Self::lock).collect()
+	}
+
+	/// Calls `f` with the value of this node and the value of each of [`Neighbors::outgoing`] in order, e.g. to fold the values of the neighbors into this node. Self-loops and dropped neighbors are skipped. Returns `false` if this `Internode` is dropped already.
+	///
+	/// The neighbors are collected under the lock like [`Internode::outgoing_snapshot`] first, and then this node and all the neighbors are locked at once in the order of their addresses, so this doesn't deadlock with other threads doing the same, e.g. on a neighbor having this node as its outgoing neighbor.
+	pub fn absorb_outgoing(&self, f: impl Fn(&mut T, &T)) -> bool {
+		let Some(outgoing) = self.outgoing_snapshot() else { return false };
+		let mut nodes = outgoing.iter().chain([self]).collect::<Vec<_>>();
+		nodes.sort_by_key(|node| Arc::as_ptr(&node.0));
+		nodes.dedup();
+		let mut guards = nodes.iter().map(|node| node.lock()).collect::<Vec<_>>();
+		let position = |node: &Self| {
+			nodes.binary_search_by_key(&Arc::as_ptr(&node.0), |node| Arc::as_ptr(&node.0)).unwrap()
+		};
+		let Some(mut guard) = guards[position(self)].take() else { return false };
+		for neighbor in outgoing.iter().filter(|&neighbor| neighbor != self) {
+			if let Some(guard_neighbor) = &guards[position(neighbor)] {
+				f(&mut guard, guard_neighbor);
+			}
+		}
+		true
+	}
+
+	/// Returns the address identifying this node, which is what [`PartialEq`] and [`Hash`] of `Internode` are based on. It's stable as long as any `Internode` to the node is held.
+	pub fn as_raw(&self) -> *const () { Arc::as_ptr(&self.0) as *const () }
+
+	/// Reconstructs an `Internode` from the address returned by [`Internode::as_raw`].
+	///
+	/// # Safety
+	///
+	/// `ptr` must be returned by [`Internode::as_raw`] on an `Internode<T>` of the same `T`, and any `Internode` to the same node must be held until this returns, so that the allocation isn't freed. The same caveats as [`Arc::from_raw`] apply.
+	pub unsafe fn from_raw(ptr: *const ()) -> Self {
+		let ptr = ptr as *const InternodeImpl<T>;
+		unsafe {
+			Arc::increment_strong_count(ptr);
+			Self(Arc::from_raw(ptr))
+		}
 	}
 
 	/// Tries to anchor this `Internode` into a `Node`.
@@ -45,99 +231,1470 @@ impl<T: Neighbors> Internode<T> {
 
 	pub(crate) fn is_anchored(&self) -> bool { self.anchor().lock().unwrap().is_some() }
 
+	/// Makes a reference to this node which keeps neither the value nor the node itself alive.
+	pub(crate) fn downgrade_weak(&self) -> Weak<InternodeImpl<T>> { Arc::downgrade(&self.0) }
+
+	pub(crate) fn upgrade_weak(weak: &Weak<InternodeImpl<T>>) -> Option<Self> {
+		weak.upgrade().map(Self)
+	}
+
 	pub(crate) fn anchor_upgraded(&self) -> Option<Arc<Anchor<T>>> {
 		self.anchor().lock().unwrap().as_ref().and_then(Weak::upgrade)
 	}
 
-	pub(crate) fn should_live(&self) -> bool {
-		std::iter::once(self.clone())
-			.chain(self.dfs_incoming().skip(1))
-			.chain(self.dfs_outgoing().skip(1))
-			.any(|node| node.is_anchored())
+	/// Drops the values of all the nodes connected to this node regarding edges as undirected, unless any of them is anchored. The connected nodes are traversed only once, and the traversal stops as soon as an anchored node is found.
+	pub(crate) fn release_unless_anchored(&self) {
+		let mut search = VecDeque::from([self.clone()]);
+		let mut visited = HashSet::new();
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				if node.is_anchored() {
+					return;
+				}
+				search.extend(node.neighbors(Direction::Both));
+			}
+		}
+		let values = visited.iter().filter_map(Self::take_value);
+		drop(values.collect::<Vec<_>>());
 	}
 
-	pub(crate) fn release(&self) {
-		let mut guard = self.value().lock().unwrap();
-		if let Some(value) = guard.take() {
+	/// Collects the neighbors in the given direction under the lock. Will be empty if this `Internode` is dropped already.
+	pub(crate) fn neighbors(&self, direction: Direction) -> Vec<Self> {
+		self.lock().map(|guard| direction.neighbors(self, &*guard)).unwrap_or_default()
+	}
+
+	/// Collects the nodes reachable in the given direction including this node, by a depth-first search whose visited set is the result itself.
+	pub(crate) fn reachable(&self, direction: Direction) -> HashSet<Self> {
+		let mut visited = HashSet::new();
+		let mut search = vec![self.clone()];
+		while let Some(node) = search.pop() {
+			if !visited.contains(&node) {
+				search.extend(node.neighbors(direction));
+				visited.insert(node);
+			}
+		}
+		visited
+	}
+
+	/// Maps each live node reachable in the given direction to its parent in the breadth-first search tree, locking each node only once.
+	pub(crate) fn bfs_tree(&self, direction: Direction) -> HashMap<Self, Self> {
+		let mut parents = HashMap::new();
+		let mut visited = HashSet::from([self.clone()]);
+		let mut search = VecDeque::from([self.clone()]);
+		while let Some(node) = search.pop_front() {
+			let Some(neighbors) = node.lock().map(|guard| direction.neighbors(&node, &*guard))
+			else {
+				parents.remove(&node);
+				continue;
+			};
+			for neighbor in neighbors {
+				if visited.insert(neighbor.clone()) {
+					parents.insert(neighbor.clone(), node.clone());
+					search.push_back(neighbor);
+				}
+			}
+		}
+		parents
+	}
+
+	/// Finds a node on a cycle reachable in the given direction, by a depth-first search which stops at the first edge back to a node on the search path.
+	pub(crate) fn find_cycle(&self, direction: Direction) -> Option<Self> {
+		let mut path = HashSet::from([self.clone()]);
+		let mut finished = HashSet::new();
+		let mut stack = vec![(self.clone(), self.neighbors(direction).into_iter())];
+		while let Some((node, neighbors)) = stack.last_mut() {
+			if let Some(neighbor) = neighbors.next() {
+				if path.contains(&neighbor) {
+					return Some(neighbor);
+				}
+				if !finished.contains(&neighbor) {
+					path.insert(neighbor.clone());
+					let neighbors = neighbor.neighbors(direction).into_iter();
+					stack.push((neighbor, neighbors));
+				}
+			} else {
+				path.remove(node);
+				finished.insert(node.clone());
+				stack.pop();
+			}
+		}
+		None
+	}
+
+	/// Performs a depth-first search in the given direction, yielding each node after all the nodes reachable from it but not yet visited. Nodes on the search path are regarded as visited, so a cycle doesn't prevent its nodes from being yielded.
+	pub(crate) fn dfs_postorder(
+		&self,
+		direction: Direction,
+	) -> impl '_ + FusedIterator<Item = Self> {
+		Gen::new(move |co| async move {
+			let mut visited = HashSet::from([self.clone()]);
+			let mut stack = vec![(self.clone(), self.neighbors(direction).into_iter())];
+			while let Some((_, neighbors)) = stack.last_mut() {
+				if let Some(neighbor) = neighbors.next() {
+					if visited.insert(neighbor.clone()) {
+						let neighbors = neighbor.neighbors(direction).into_iter();
+						stack.push((neighbor, neighbors));
+					}
+				} else if let Some((node, _)) = stack.pop() {
+					co.yield_(node).await;
+				}
+			}
+		})
+		.into_iter()
+		.fuse()
+	}
+
+	/// Collects the live nodes reachable in the given direction along with their distances, in breadth-first order. Each node is locked only once.
+	pub(crate) fn distances(&self, direction: Direction) -> Vec<(Self, usize)> {
+		let mut search = VecDeque::from([(self.clone(), 0)]);
+		let mut visited = HashSet::from([self.clone()]);
+		let mut distances = Vec::new();
+		while let Some((node, distance)) = search.pop_front() {
+			let Some(guard) = node.lock() else { continue };
+			for neighbor in direction.neighbors(&node, &*guard) {
+				if visited.insert(neighbor.clone()) {
+					search.push_back((neighbor, distance + 1));
+				}
+			}
 			drop(guard);
-			for node in value.incoming().chain(value.outgoing()) {
-				node.release()
+			distances.push((node, distance));
+		}
+		distances
+	}
+
+	/// Visits the live nodes reachable in the given direction in breadth-first order, locking each node only once while `f` is called with its value.
+	pub(crate) fn visit(&self, direction: Direction, mut f: impl FnMut(&Self, &T)) {
+		let mut search = VecDeque::from([self.clone()]);
+		let mut visited = HashSet::new();
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				if let Some(guard) = node.lock() {
+					f(&node, &guard);
+					search.extend(direction.neighbors(&node, &*guard));
+				}
 			}
 		}
 	}
 
+	/// Collects the nodes having no incoming neighbor, among the nodes connected to this node regarding edges as undirected, in breadth-first order. Will be empty if every node has incoming neighbors, e.g. the connected graph is a cycle.
+	pub fn component_roots(&self) -> Vec<Self> {
+		let mut roots = Vec::new();
+		self.visit(Direction::Both, |node, value| {
+			if node.incoming_of(value).next().is_none() {
+				roots.push(node.clone());
+			}
+		});
+		roots
+	}
+
+	/// Counts the live nodes connected to this node including itself, regarding edges as undirected. Returns `0` if this node is dropped.
+	pub fn component_size(&self) -> usize {
+		let mut size = 0;
+		self.visit(Direction::Both, |_, _| size += 1);
+		size
+	}
+
 	/// Blocks until the internal `Mutex` can be locked and calls [`Neighbors::outgoing`].
 	pub fn outgoing(&self) -> impl '_ + Iterator<Item = Self> {
 		self.lock().into_iter().flat_map(InternodeMutexGuard::outgoing)
 	}
 
-	/// Blocks until the internal `Mutex` can be locked and calls [`Neighbors::incoming`].
+	/// Blocks until the internal `Mutex` can be locked and calls [`Neighbors::incoming`], followed by the incoming neighbors recorded by [`Internode::connect_indexed`].
 	pub fn incoming(&self) -> impl '_ + Iterator<Item = Self> {
-		self.lock().into_iter().flat_map(InternodeMutexGuard::incoming)
+		self.lock().into_iter().flat_map(InternodeMutexGuard::incoming).chain(self.predecessors())
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and collects [`Neighbors::outgoing`]. Will be `None` if this `Internode` is dropped already.
+	///
+	/// Unlike [`Internode::outgoing`], the lock is released before this returns, so the neighbors can be locked or edited while iterating over the result.
+	pub fn outgoing_snapshot(&self) -> Option<Vec<Self>> {
+		self.lock().map(|guard| guard.outgoing().collect())
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and collects [`Neighbors::incoming`]. Will be `None` if this `Internode` is dropped already.
+	///
+	/// Unlike [`Internode::incoming`], the lock is released before this returns, so the neighbors can be locked or edited while iterating over the result.
+	pub fn incoming_snapshot(&self) -> Option<Vec<Self>> {
+		self.lock().map(|guard| self.incoming_of(&guard).collect())
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and collects both [`Neighbors::outgoing`] and [`Neighbors::incoming`] under the same lock, so that they're consistent with each other unlike separate calls of [`Internode::outgoing_snapshot`] and [`Internode::incoming_snapshot`]. Will be `None` if this `Internode` is dropped already.
+	pub fn both_directions(&self) -> Option<(Vec<Self>, Vec<Self>)> {
+		self.lock().map(|guard| (T::outgoing(&guard).collect(), self.incoming_of(&guard).collect()))
 	}
 
-	/// Performs a depth-first search by recursively calling [`Internode::outgoing`]. Includes the starting node first.
-	pub fn dfs_outgoing(&self) -> impl '_ + Iterator<Item = Self> {
+	/// Blocks until the internal `Mutex` can be locked and returns the first of [`Neighbors::outgoing`], e.g. the "next" pointer of a linked list. Will be `None` if there's no outgoing neighbor, or this `Internode` is dropped already.
+	pub fn outgoing_first(&self) -> Option<Self> { self.lock()?.outgoing().next() }
+
+	/// Blocks until the internal `Mutex` can be locked and returns the last of [`Neighbors::outgoing`]. Will be `None` if there's no outgoing neighbor, or this `Internode` is dropped already.
+	pub fn outgoing_last(&self) -> Option<Self> { self.lock()?.outgoing().last() }
+
+	/// Same as [`Internode::outgoing_snapshot`], but collects at most `n` neighbors, without iterating over the rest. Will be empty if this `Internode` is dropped already.
+	///
+	/// [`Internode::outgoing_len_hint`] tells how many there are in total, e.g. to show "N of M".
+	pub fn outgoing_take(&self, n: usize) -> Vec<Self> {
+		self.lock().map(|guard| guard.outgoing().take(n).collect()).unwrap_or_default()
+	}
+
+	/// Collects [`Neighbors::outgoing`] under the lock like [`Internode::outgoing_snapshot`], and calls `f` for each of them until it returns an `Err`, which is returned then. Does nothing if this `Internode` is dropped already.
+	///
+	/// The lock is released before calling `f`, so it's free to lock or edit the neighbors.
+	pub fn try_for_each_outgoing<E>(&self, f: impl FnMut(Self) -> Result<(), E>) -> Result<(), E> {
+		self.outgoing_snapshot().into_iter().flatten().try_for_each(f)
+	}
+
+	/// Collects [`Neighbors::outgoing`] under the lock like [`Internode::outgoing_snapshot`], and returns an iterator upgrading each of them into a `Node` lazily. Dropped neighbors are skipped.
+	///
+	/// Note that each `Node` yielded anchors the entire connected graph as long as it's held.
+	pub fn outgoing_nodes(&self) -> impl FusedIterator<Item = Node<T>> {
+		self.outgoing_snapshot().into_iter().flatten().filter_map(|node| node.upgrade())
+	}
+
+	/// Collects [`Neighbors::outgoing`] under the lock like [`Internode::outgoing_snapshot`], and returns an iterator over them without duplicates, keeping the first occurrences. Will be empty if this `Internode` is dropped already.
+	///
+	/// This allocates a `HashSet` of the neighbors besides the snapshot, to find duplicates.
+	pub fn outgoing_unique(&self) -> impl FusedIterator<Item = Self> {
+		let mut visited = HashSet::new();
+		let mut nodes = self.outgoing_snapshot().unwrap_or_default();
+		nodes.retain(|node| visited.insert(node.clone()));
+		nodes.into_iter()
+	}
+
+	/// Collects [`Neighbors::outgoing`] under the lock like [`Internode::outgoing_snapshot`], and splits them into chunks of `size` in order, where the last chunk may be smaller. Will be empty if this `Internode` is dropped already.
+	///
+	/// # Panics
+	///
+	/// Panics if `size` is `0`.
+	pub fn outgoing_chunks(&self, size: usize) -> Vec<Vec<Self>> {
+		assert!(size != 0, "chunk size must be non-zero");
+		self.outgoing_snapshot().unwrap_or_default().chunks(size).map(<[_]>::to_vec).collect()
+	}
+
+	/// Splits [`Neighbors::outgoing`] into the ones satisfying `pred` and the others, in order. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The neighbors are collected under the lock like [`Internode::outgoing_snapshot`], and `pred` is called after the lock is released, so it's free to lock the neighbors.
+	pub fn partition_outgoing(
+		&self,
+		pred: impl Fn(&Self) -> bool,
+	) -> Option<(Vec<Self>, Vec<Self>)> {
+		Some(self.outgoing_snapshot()?.into_iter().partition(pred))
+	}
+
+	/// Groups [`Neighbors::outgoing`] by the key computed from their values. Dropped neighbors are skipped.
+	///
+	/// The neighbors are collected under the lock like [`Internode::outgoing_snapshot`] first, and then each of them is locked in turn to compute its key, so no two nodes are locked at once and this doesn't deadlock with other threads.
+	pub fn group_outgoing_by<K: Eq + Hash>(&self, key: impl Fn(&T) -> K) -> HashMap<K, Vec<Self>> {
+		let mut groups = HashMap::<_, Vec<_>>::new();
+		for node in self.outgoing_snapshot().unwrap_or_default() {
+			let Some(guard) = node.lock() else { continue };
+			let key = key(&guard);
+			drop(guard);
+			groups.entry(key).or_default().push(node);
+		}
+		groups
+	}
+
+	/// Returns an iterator over the outgoing neighbors of [`Neighbors::outgoing`], i.e. the nodes reachable by two edges, deduplicated and excluding this node. Each of them may be also an outgoing neighbor of this node.
+	///
+	/// This node and each neighbor are locked once in turn while their neighbors are collected, before this returns.
+	pub fn two_hop_outgoing(&self) -> impl FusedIterator<Item = Self> {
+		let mut visited = HashSet::from([self.clone()]);
+		self.outgoing_snapshot()
+			.unwrap_or_default()
+			.iter()
+			.flat_map(|node| node.outgoing_snapshot().unwrap_or_default())
+			.filter(|node| visited.insert(node.clone()))
+			.collect::<Vec<_>>()
+			.into_iter()
+	}
+
+	/// Indexes the nodes reachable via [`Internode::outgoing`] including this node by the key computed from their values. Dropped nodes are skipped.
+	///
+	/// The nodes are visited in breadth-first order, locking each node only once while `key` is called, and if multiple nodes have the same key, the last visited one wins.
+	pub fn index_outgoing_by<K: Eq + Hash>(&self, key: impl Fn(&T) -> K) -> HashMap<K, Self> {
+		let mut index = HashMap::new();
+		self.visit(Direction::Outgoing, |node, value| {
+			index.insert(key(value), node.clone());
+		});
+		index
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and returns [`Iterator::size_hint`] of [`Neighbors::outgoing`], without iterating over it. Will be `(0, Some(0))` if this `Internode` is dropped already.
+	pub fn outgoing_len_hint(&self) -> (usize, Option<usize>) {
+		self.lock().map_or((0, Some(0)), |guard| guard.outgoing().size_hint())
+	}
+
+	/// Counts [`Neighbors::outgoing`] satisfying `pred`. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The neighbors are collected under the lock like [`Internode::outgoing_snapshot`], and `pred` is called after the lock is released, so it's free to lock the neighbors.
+	pub fn count_outgoing_matching(&self, pred: impl Fn(&Self) -> bool) -> Option<usize> {
+		self.outgoing_snapshot().map(|nodes| nodes.iter().filter(|node| pred(node)).count())
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and counts the edges to `target` in [`Neighbors::outgoing`], i.e. the multiplicity of the edge. Will be `None` if this `Internode` is dropped already.
+	pub fn edge_multiplicity_to(&self, target: &Self) -> Option<usize> {
+		self.lock().map(|guard| guard.outgoing().filter(|node| node == target).count())
+	}
+
+	/// Walks a chain from this node by following the first of [`Neighbors::outgoing`] step by step, until reaching a node whose value satisfies `stop`, and returns the visited nodes including that one. The walk also ends at a node without outgoing neighbors, before a dropped node, or before revisiting a node on a cycle.
+	///
+	/// Each node is locked only once, while `stop` is called and the first neighbor is taken.
+	pub fn follow_first_outgoing_until(&self, stop: impl Fn(&T) -> bool) -> Vec<Self> {
+		let mut path = Vec::new();
+		let mut visited = HashSet::new();
+		let mut next = Some(self.clone());
+		while let Some(node) = next.filter(|node| visited.insert(node.clone())) {
+			let Some(guard) = node.lock() else { break };
+			next = if stop(&guard) { None } else { T::outgoing(&guard).next() };
+			drop(guard);
+			path.push(node);
+		}
+		path
+	}
+
+	/// Follows [`Neighbors::outgoing`] step by step, keeping only the neighbors whose values satisfy the predicate for each step, and returns the nodes reached after all the steps without duplicates.
+	///
+	/// Will be just this node if `preds` is empty. Dropped nodes are skipped.
+	#[allow(clippy::type_complexity)]
+	pub fn follow_outgoing(&self, preds: &[Box<dyn Fn(&T) -> bool>]) -> Vec<Self> {
+		let mut nodes = vec![self.clone()];
+		for pred in preds {
+			let mut visited = HashSet::new();
+			nodes = nodes
+				.iter()
+				.flat_map(|node| node.neighbors(Direction::Outgoing))
+				.filter(|node| visited.insert(node.clone()))
+				.filter(|node| node.lock().is_some_and(|guard| pred(&guard)))
+				.collect();
+		}
+		nodes
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and collects [`Neighbors::outgoing`] in reverse order. Will be `None` if this `Internode` is dropped already.
+	///
+	/// This allocates a `Vec` to hold the snapshot, since [`Neighbors::Iter`] isn't necessarily a [`DoubleEndedIterator`].
+	pub fn outgoing_rev(&self) -> Option<Vec<Self>> {
+		self.lock().map(|guard| {
+			let mut nodes = guard.outgoing().collect::<Vec<_>>();
+			nodes.reverse();
+			nodes
+		})
+	}
+
+	/// Counts how many nodes reachable via [`Internode::outgoing`] have each out-degree. Includes the starting node.
+	pub fn degree_histogram_outgoing(&self) -> BTreeMap<usize, usize> {
+		let mut histogram = BTreeMap::new();
+		self.visit(Direction::Outgoing, |_, value| {
+			*histogram.entry(value.outgoing().count()).or_default() += 1
+		});
+		histogram
+	}
+
+	/// Computes the degree centrality of each node in the connected graph, i.e. the number of its distinct neighbors regarding edges as undirected, which combines outgoing and incoming neighbors and excludes itself, divided by the number of the other nodes. Dropped nodes are excluded. A single isolated node gets `0.0`.
+	///
+	/// This runs on a snapshot of the connected graph taken at once.
+	pub fn degree_centrality_undirected(&self) -> HashMap<Self, f64> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let others = subgraph.len().saturating_sub(1).max(1) as f64;
+		(0..subgraph.len())
+			.map(|index| {
+				(subgraph.node(index).clone(), subgraph.undirected(index).len() as f64 / others)
+			})
+			.collect()
+	}
+
+	/// Calls `f` with the mutable value of each node reachable via [`Internode::outgoing`], in breadth-first order. Includes the starting node first, and skips dropped nodes.
+	///
+	/// Each node is locked exactly once, while `f` is called with its value and then its neighbors are collected, so edits to the neighbors by `f` affect which nodes are visited. No two nodes are locked at once, so this doesn't deadlock unless `f` locks the node it receives.
+	pub fn for_each_value_mut_outgoing(&self, mut f: impl FnMut(&mut T)) {
+		let mut search = VecDeque::from([self.clone()]);
+		let mut visited = HashSet::new();
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				if let Some(mut guard) = node.lock() {
+					f(&mut guard);
+					search.extend(T::outgoing(&guard));
+				}
+			}
+		}
+	}
+
+	/// Folds the values of the nodes reachable via [`Internode::outgoing`] into an accumulator, in breadth-first order. Includes the starting node first, and skips dropped nodes.
+	///
+	/// Each node is locked only once, while `f` is called with its value.
+	pub fn map_reduce_outgoing<A>(&self, init: A, mut f: impl FnMut(A, &T) -> A) -> A {
+		let mut acc = Some(init);
+		self.visit(Direction::Outgoing, |_, value| acc = acc.take().map(|acc| f(acc, value)));
+		acc.unwrap()
+	}
+
+	/// Folds the edges among the nodes reachable via [`Internode::outgoing`] into an accumulator, in breadth-first order of their sources. Parallel edges are folded only once.
+	///
+	/// `f` is called with the source and the target of each edge, while no node is locked.
+	pub fn fold_edges_outgoing<A>(&self, init: A, mut f: impl FnMut(A, &Self, &Self) -> A) -> A {
+		let mut acc = init;
+		let mut search = VecDeque::from([self.clone()]);
+		let mut visited = HashSet::new();
+		let mut edges = HashSet::new();
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				for neighbor in node.neighbors(Direction::Outgoing) {
+					if edges.insert((node.clone(), neighbor.clone())) {
+						acc = f(acc, &node, &neighbor);
+						search.push_back(neighbor);
+					}
+				}
+			}
+		}
+		acc
+	}
+
+	/// Collects the nodes reachable via [`Internode::outgoing`] in breadth-first order, along with [`Neighbors::outgoing`] of each of them. Each node is locked only once.
+	pub fn collect_outgoing(&self) -> (Vec<Self>, HashMap<Self, Vec<Self>>) {
+		let mut nodes = Vec::new();
+		let mut adjacency = HashMap::new();
+		self.visit(Direction::Outgoing, |node, value| {
+			nodes.push(node.clone());
+			adjacency.insert(node.clone(), value.outgoing().collect());
+		});
+		(nodes, adjacency)
+	}
+
+	/// Builds the adjacency matrix of the nodes reachable via [`Internode::outgoing`], where `matrix[i][j]` is `true` if there's an edge from `nodes[i]` to `nodes[j]`. The nodes are ordered in breadth-first order.
+	///
+	/// Note that the matrix takes quadratic space in the number of nodes, so this is meant for small graphs.
+	pub fn to_adjacency_matrix_outgoing(&self) -> (Vec<Self>, Vec<Vec<bool>>) {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let mut matrix = vec![vec![false; subgraph.len()]; subgraph.len()];
+		for (from, row) in matrix.iter_mut().enumerate() {
+			for &to in subgraph.outgoing(from) {
+				row[to] = true;
+			}
+		}
+		(subgraph.nodes().to_vec(), matrix)
+	}
+
+	/// Renders the edges among the nodes reachable via [`Internode::outgoing`] as CSV with the header `from,to`, where each node is written as the id returned by `id_of`. Parallel edges are written only once.
+	pub fn to_edge_list_csv(&self, id_of: impl Fn(&T) -> String) -> String {
+		fn escape(field: &str) -> String {
+			if field.contains([',', '"', '\n', '\r']) {
+				format!("\"{}\"", field.replace('"', "\"\""))
+			} else {
+				field.to_string()
+			}
+		}
+		let mut ids = HashMap::new();
+		let mut adjacency = Vec::new();
+		self.visit(Direction::Outgoing, |node, value| {
+			ids.insert(node.clone(), escape(&id_of(value)));
+			adjacency.push((node.clone(), value.outgoing().collect::<Vec<_>>()));
+		});
+		let mut edges = HashSet::new();
+		let mut csv = String::from("from,to\n");
+		for (from, targets) in &adjacency {
+			for to in targets {
+				if let Some(id_to) = ids.get(to) {
+					if edges.insert((from, to)) {
+						csv += &format!("{},{}\n", ids[from], id_to);
+					}
+				}
+			}
+		}
+		csv
+	}
+
+	/// Takes a [`Subgraph`] of the nodes reachable via [`Internode::outgoing`] whose values satisfy `pred`, and the edges among them.
+	///
+	/// The traversal goes through the nodes not satisfying `pred` as well, so the result may not be connected.
+	pub fn subgraph_filtered_outgoing(&self, pred: impl Fn(&T) -> bool) -> Subgraph<T> {
+		Subgraph::collect(self, Direction::Outgoing, pred)
+	}
+
+	/// Computes the `k`-core of the connected graph, i.e. the maximal set of nodes where each node has at least `k` neighbors within the set.
+	///
+	/// Edges are regarded as undirected, and a degree is the number of distinct neighbors (see [`Subgraph::undirected`]). Works on a [`Subgraph`] snapshot, so the graph itself is not modified.
+	pub fn k_core_undirected(&self, k: usize) -> Vec<Self> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let neighbors =
+			(0..subgraph.len()).map(|index| subgraph.undirected(index)).collect::<Vec<_>>();
+		let mut degrees = neighbors.iter().map(Vec::len).collect::<Vec<_>>();
+		let mut removed = vec![false; subgraph.len()];
+		let mut search =
+			(0..subgraph.len()).filter(|&index| degrees[index] < k).collect::<VecDeque<_>>();
+		while let Some(index) = search.pop_front() {
+			if !std::mem::replace(&mut removed[index], true) {
+				for &neighbor in &neighbors[index] {
+					degrees[neighbor] -= 1;
+					if degrees[neighbor] < k && !removed[neighbor] {
+						search.push_back(neighbor);
+					}
+				}
+			}
+		}
+		(0..subgraph.len())
+			.filter(|&index| !removed[index])
+			.map(|index| subgraph.node(index).clone())
+			.collect()
+	}
+
+	/// Partitions the connected graph into communities by label propagation, regarding edges as undirected.
+	///
+	/// Every node starts with its own label, and then repeatedly adopts the label most common among its neighbors, keeping the current one on ties if possible or taking the earliest discovered one otherwise, until no label changes or 100 rounds pass. The nodes are visited in an order shuffled by `seed` in each round, so the result is deterministic for the same `seed` and graph. Communities are ordered by their earliest discovered nodes, and each is in breadth-first order of discovery starting from this node.
+	///
+	/// This runs on a snapshot of the connected graph taken at once, so edits during the computation aren't reflected.
+	pub fn communities_undirected(&self, seed: u64) -> Vec<Vec<Self>> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let neighbors =
+			(0..subgraph.len()).map(|index| subgraph.undirected(index)).collect::<Vec<_>>();
+		let mut labels = (0..subgraph.len()).collect::<Vec<_>>();
+		let mut order = (0..subgraph.len()).collect::<Vec<_>>();
+		// SplitMix64
+		let mut state = seed;
+		let mut random = move || {
+			state = state.wrapping_add(0x9e3779b97f4a7c15);
+			let z = (state ^ (state >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+			let z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+			z ^ (z >> 31)
+		};
+		for _ in 0..100 {
+			for i in (1..order.len()).rev() {
+				order.swap(i, (random() % (i as u64 + 1)) as usize);
+			}
+			let mut changed = false;
+			for &index in &order {
+				let mut counts = BTreeMap::new();
+				for &neighbor in &neighbors[index] {
+					*counts.entry(labels[neighbor]).or_insert(0) += 1;
+				}
+				let Some(&max) = counts.values().max() else { continue };
+				if counts.get(&labels[index]) == Some(&max) {
+					continue;
+				}
+				labels[index] = counts.into_iter().find(|&(_, count)| count == max).unwrap().0;
+				changed = true;
+			}
+			if !changed {
+				break;
+			}
+		}
+		let mut communities = BTreeMap::<_, Vec<_>>::new();
+		for (index, label) in labels.into_iter().enumerate() {
+			communities.entry(label).or_default().push(subgraph.node(index).clone());
+		}
+		let mut communities = communities.into_values().collect::<Vec<_>>();
+		communities.sort_by_key(|community| subgraph.index_of(&community[0]));
+		communities
+	}
+
+	/// Returns `true` if the connected graph is bipartite regarding edges as undirected, i.e. the nodes can be colored in two colors so that no edge connects nodes of the same color. Self-loops make it non-bipartite.
+	///
+	/// This runs on a snapshot of the connected graph taken at once.
+	pub fn is_bipartite_undirected(&self) -> bool {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		if (0..subgraph.len()).any(|index| subgraph.outgoing(index).contains(&index)) {
+			return false;
+		}
+		let mut colors = vec![None; subgraph.len()];
+		for start in 0..subgraph.len() {
+			if colors[start].is_some() {
+				continue;
+			}
+			colors[start] = Some(false);
+			let mut search = VecDeque::from([start]);
+			while let Some(index) = search.pop_front() {
+				let color = colors[index].unwrap();
+				for neighbor in subgraph.undirected(index) {
+					match colors[neighbor] {
+						None => {
+							colors[neighbor] = Some(!color);
+							search.push_back(neighbor);
+						}
+						Some(neighbor_color) if neighbor_color == color => return false,
+						Some(_) => {}
+					}
+				}
+			}
+		}
+		true
+	}
+
+	/// Collects the articulation points of the connected graph regarding edges as undirected, i.e. the nodes whose removal would disconnect the rest, in breadth-first order. Parallel edges and pairs of edges in both directions count as single edges.
+	///
+	/// This runs the low-link algorithm on a snapshot of the connected graph taken at once.
+	pub fn articulation_points_undirected(&self) -> Vec<Self> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let (times, lows, parents) = subgraph.low_links();
+		let mut is_articulation = vec![false; subgraph.len()];
+		let mut root_children = 0;
+		for (index, parent) in parents.iter().enumerate() {
+			match *parent {
+				Some(0) => root_children += 1,
+				Some(parent) if lows[index] >= times[parent] => is_articulation[parent] = true,
+				_ => {}
+			}
+		}
+		if root_children > 1 {
+			is_articulation[0] = true;
+		}
+		(0..subgraph.len())
+			.filter(|&index| is_articulation[index])
+			.map(|index| subgraph.node(index).clone())
+			.collect()
+	}
+
+	/// Collects the bridges of the connected graph regarding edges as undirected, i.e. the edges whose removal would disconnect the rest. Each bridge is a pair of its endpoints, the smaller one first in terms of [`Ord`]. Parallel edges and pairs of edges in both directions collapse into a single undirected edge, which can still be a bridge.
+	///
+	/// This runs the low-link algorithm on a snapshot of the connected graph taken at once.
+	pub fn bridges_undirected(&self) -> Vec<(Self, Self)> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let (times, lows, parents) = subgraph.low_links();
+		parents
+			.iter()
+			.enumerate()
+			.filter_map(|(index, parent)| parent.map(|parent| (parent, index)))
+			.filter(|&(parent, index)| lows[index] > times[parent])
+			.map(|(parent, index)| {
+				let (parent, node) = (subgraph.node(parent).clone(), subgraph.node(index).clone());
+				if parent < node {
+					(parent, node)
+				} else {
+					(node, parent)
+				}
+			})
+			.collect()
+	}
+
+	/// Returns `true` if the connected graph forms a tree regarding edges as undirected, i.e. it's connected by the edges of [`Neighbors::outgoing`] and has no cycle among them. Self-loops, parallel edges, and pairs of edges in both directions count as cycles. A single isolated node is a tree. Returns `false` if this `Internode` is dropped already.
+	///
+	/// This runs on a snapshot of the connected graph taken at once.
+	pub fn is_tree_undirected(&self) -> bool {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		if subgraph.len().checked_sub(1) != Some(subgraph.edge_count()) {
+			return false;
+		}
+		// Union-find: n - 1 edges form a tree iff each of them joins two separate trees.
+		let mut parents = (0..subgraph.len()).collect::<Vec<_>>();
+		fn root(parents: &mut [usize], mut index: usize) -> usize {
+			while parents[index] != index {
+				parents[index] = parents[parents[index]];
+				index = parents[index];
+			}
+			index
+		}
+		(0..subgraph.len()).all(|from| {
+			subgraph.outgoing(from).iter().all(|&to| {
+				let (from, to) = (root(&mut parents, from), root(&mut parents, to));
+				parents[from] = to;
+				from != to
+			})
+		})
+	}
+
+	/// Returns `true` if there's no cycle among the nodes reachable via [`Internode::outgoing`], including self-loops.
+	///
+	/// This stops searching as soon as any cycle is found.
+	pub fn is_dag_outgoing(&self) -> bool { self.find_cycle(Direction::Outgoing).is_none() }
+
+	/// Returns `true` if there's a cycle among the nodes reachable via [`Internode::outgoing`], including self-loops. The opposite of [`Internode::is_dag_outgoing`].
+	///
+	/// This stops searching as soon as any cycle is found.
+	pub fn has_cycle_outgoing(&self) -> bool { self.find_cycle(Direction::Outgoing).is_some() }
+
+	/// Returns `true` if there's a cycle among the nodes reachable via [`Internode::incoming`], including self-loops.
+	///
+	/// This stops searching as soon as any cycle is found.
+	pub fn has_cycle_incoming(&self) -> bool { self.find_cycle(Direction::Incoming).is_some() }
+
+	/// Sorts the nodes reachable via [`Internode::outgoing`] so that every node comes after its outgoing neighbors, i.e. dependencies before dependents if edges point to dependencies.
+	///
+	/// Fails if there's a cycle among the reachable nodes.
+	pub fn topological_outgoing(&self) -> Result<Vec<Self>, CycleError<T>> {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let order = subgraph
+			.topological_order()
+			.map_err(|index| CycleError::new(subgraph.node(index).clone()))?;
+		Ok(order.into_iter().rev().map(|index| subgraph.node(index).clone()).collect())
+	}
+
+	/// Computes the length of the longest path to each node reachable via [`Internode::outgoing`], from any node having no incoming neighbors among them. Such source nodes get `0`.
+	///
+	/// Fails if there's a cycle among the reachable nodes.
+	pub fn longest_path_depth_outgoing(&self) -> Result<HashMap<Self, usize>, CycleError<T>> {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let depths = subgraph
+			.longest_path_depths()
+			.map_err(|index| CycleError::new(subgraph.node(index).clone()))?;
+		Ok(subgraph.nodes().iter().cloned().zip(depths).collect())
+	}
+
+	/// Counts the distinct paths from this node to `target` via [`Internode::outgoing`], without enumerating them. A node has exactly one path to itself. Parallel edges make distinct paths. The count saturates at [`u128::MAX`].
+	///
+	/// Fails if there's a cycle among the nodes reachable from this node, even if it isn't on any path to `target`.
+	pub fn count_paths_outgoing(&self, target: &Self) -> Result<u128, CycleError<T>> {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let order = subgraph
+			.topological_order()
+			.map_err(|index| CycleError::new(subgraph.node(index).clone()))?;
+		let Some(target) = subgraph.index_of(target) else { return Ok(0) };
+		let mut counts = vec![0u128; subgraph.len()];
+		if let Some(start) = subgraph.index_of(self) {
+			counts[start] = 1;
+		}
+		for index in order {
+			for &neighbor in subgraph.outgoing(index) {
+				counts[neighbor] = counts[neighbor].saturating_add(counts[index]);
+			}
+		}
+		Ok(counts[target])
+	}
+
+	/// Groups the nodes reachable via [`Internode::outgoing`] into layers, where the first layer consists of the nodes having no incoming neighbors among them, and each following layer consists of the nodes whose incoming neighbors are all in the preceding layers. That is, a node is in the layer at the index of its [`Internode::longest_path_depth_outgoing`]. Nodes in each layer are in breadth-first order of discovery.
+	///
+	/// Fails if there's a cycle among the reachable nodes.
+	pub fn topo_layers_outgoing(&self) -> Result<Vec<Vec<Self>>, CycleError<T>> {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let depths = subgraph
+			.longest_path_depths()
+			.map_err(|index| CycleError::new(subgraph.node(index).clone()))?;
+		let mut layers = vec![Vec::new(); depths.iter().max().map_or(0, |depth| depth + 1)];
+		for (node, depth) in subgraph.nodes().iter().zip(depths) {
+			layers[depth].push(node.clone());
+		}
+		Ok(layers)
+	}
+
+	/// Maps each node reachable via [`Internode::incoming`] to its parent in the breadth-first search tree, i.e. the next node on a shortest path toward this node. This node itself isn't contained. Dropped nodes are skipped.
+	///
+	/// Each node is locked only once.
+	pub fn bfs_incoming_tree(&self) -> HashMap<Self, Self> { self.bfs_tree(Direction::Incoming) }
+
+	/// Maps each node reachable via [`Internode::outgoing`] to its predecessor on a shortest path from this node, so that the shortest path to any of them can be reconstructed by following the predecessors. This node itself isn't contained. If there're multiple shortest paths, the predecessor discovered first in breadth-first order is taken. Dropped nodes are skipped.
+	///
+	/// Each node is locked only once.
+	pub fn shortest_path_tree_outgoing(&self) -> HashMap<Self, Self> {
+		self.bfs_tree(Direction::Outgoing)
+	}
+
+	/// Finds the lowest common ancestor of this node and `other` via [`Internode::incoming`], i.e. the first node on the chain of ancestors of `other` which is also on the chain of this node. Each node counts as its own ancestor, so if one is an ancestor of the other, that one is returned. Will be `None` if there's no common ancestor.
+	///
+	/// This assumes tree-shaped graphs where each node has at most one incoming neighbor. Otherwise, only the first of [`Neighbors::incoming`] is followed as the parent, so the result may not be the lowest among all the common ancestors. The chains stop at dropped nodes or where they loop.
+	pub fn lca_incoming(&self, other: &Self) -> Option<Self> {
+		let chain = |start: &Self| {
+			let mut chain = Vec::new();
+			let mut visited = HashSet::new();
+			let mut next = Some(start.clone());
+			while let Some(node) = next.filter(|node| visited.insert(node.clone())) {
+				let Some(guard) = node.lock() else { break };
+				next = node.incoming_of(&guard).next();
+				drop(guard);
+				chain.push(node);
+			}
+			chain
+		};
+		let ancestors = chain(self).into_iter().collect::<HashSet<_>>();
+		chain(other).into_iter().find(|node| ancestors.contains(node))
+	}
+
+	/// Collects the nodes reachable via [`Internode::incoming`] in breadth-first order, excluding this node, without going beyond the nodes in `boundary`. The nodes in `boundary` are included if reached, but their incoming neighbors are not. Dropped nodes are skipped.
+	pub fn ancestors_until(&self, boundary: &HashSet<Self>) -> Vec<Self> {
+		let mut search = VecDeque::from(self.incoming_snapshot().unwrap_or_default());
+		let mut visited = HashSet::from([self.clone()]);
+		let mut ancestors = Vec::new();
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				let Some(guard) = node.lock() else { continue };
+				if !boundary.contains(&node) {
+					search.extend(node.incoming_of(&guard));
+				}
+				drop(guard);
+				ancestors.push(node);
+			}
+		}
+		ancestors
+	}
+
+	/// Collects the nodes whose distance, i.e. the number of edges of the shortest path from this node via [`Internode::outgoing`], is exactly `k`, by a breadth-first search which stops at that distance. `k = 0` gives only this node. Dropped nodes are skipped.
+	pub fn at_distance_outgoing(&self, k: usize) -> Vec<Self> {
+		let mut visited = HashSet::from([self.clone()]);
+		let mut frontier = vec![self.clone()];
+		for _ in 0..k {
+			frontier = frontier
+				.iter()
+				.flat_map(|node| node.neighbors(Direction::Outgoing))
+				.filter(|neighbor| visited.insert(neighbor.clone()))
+				.collect();
+		}
+		frontier.retain(Self::is_alive);
+		frontier
+	}
+
+	/// Estimates the diameter, i.e. the longest distance of shortest paths via [`Internode::outgoing`], by two breadth-first searches: one from this node to find the farthest node, and another from that node.
+	///
+	/// The result is a lower bound of the diameter among the nodes reachable from this node. It's exact if they form a tree rooted at this node.
+	pub fn diameter_estimate_outgoing(&self) -> usize {
+		let Some((farthest, first)) = self.distances(Direction::Outgoing).pop() else { return 0 };
+		let second =
+			farthest.distances(Direction::Outgoing).pop().map_or(0, |(_, distance)| distance);
+		first.max(second)
+	}
+
+	/// Finds a path passing through every edge in the connected graph exactly once, following the direction of edges, by Hierholzer's algorithm. Will be `None` if there's no such path.
+	///
+	/// If the path can be a circuit, it starts and ends at this node, as long as this node has any outgoing neighbor. Works on a [`Subgraph`] snapshot of the nodes connected in either direction.
+	pub fn eulerian_path_outgoing(&self) -> Option<Vec<Self>> {
+		let subgraph = Subgraph::collect(self, Direction::Both, |_| true);
+		let balance = |index| {
+			subgraph.outgoing(index).len() as isize - subgraph.incoming(index).len() as isize
+		};
+		let mut start = None;
+		let mut ends = 0;
+		for index in 0..subgraph.len() {
+			match balance(index) {
+				0 => {}
+				1 if start.is_none() => start = Some(index),
+				-1 if ends == 0 => ends += 1,
+				_ => return None,
+			}
+		}
+		let start = match start {
+			Some(start) => start,
+			None if ends == 0 => subgraph
+				.index_of(self)
+				.filter(|&index| !subgraph.outgoing(index).is_empty())
+				.or_else(|| (0..subgraph.len()).find(|&index| !subgraph.outgoing(index).is_empty()))
+				.or(subgraph.index_of(self))?,
+			None => return None,
+		};
+		let mut used = vec![0; subgraph.len()];
+		let mut stack = vec![start];
+		let mut path = Vec::new();
+		while let Some(&index) = stack.last() {
+			if let Some(&neighbor) = subgraph.outgoing(index).get(used[index]) {
+				used[index] += 1;
+				stack.push(neighbor);
+			} else {
+				path.push(subgraph.node(index).clone());
+				stack.pop();
+			}
+		}
+		path.reverse();
+		(path.len() == subgraph.edge_count() + 1).then_some(path)
+	}
+
+	/// Drops the nodes reachable via [`Internode::outgoing`] other than this node, which have neither outgoing nor incoming neighbors themselves, and returns the number of them.
+	///
+	/// Such nodes can be still reached through the neighbors of other nodes, e.g. when only one side of edges is removed, but they're their own connected graphs regarding their own neighbors, so they aren't kept alive by other `Node`s. Nodes anchored by `Node`s to themselves are left alive. The edges to the dropped nodes remain, and traversals skip them as dropped nodes.
+	pub fn reap_isolated_outgoing(&self) -> usize {
+		let is_isolated = |node: &Self, value: &T| {
+			value.outgoing().next().is_none() && node.incoming_of(value).next().is_none()
+		};
+		let mut isolated = Vec::new();
+		self.visit(Direction::Outgoing, |node, value| {
+			if node != self && is_isolated(node, value) {
+				isolated.push(node.clone());
+			}
+		});
+		let mut count = 0;
+		for node in isolated {
+			if node.is_anchored() {
+				continue;
+			}
+			let mut guard = node.value().lock().unwrap();
+			if guard.as_ref().is_some_and(|value| is_isolated(&node, value)) {
+				node.generation().fetch_add(1, Ordering::Release);
+				let value = guard.take();
+				drop(guard);
+				drop(value);
+				count += 1;
+			}
+		}
+		count
+	}
+
+	/// Summarizes the structure of the nodes reachable via [`Internode::outgoing`] including this node. Dropped nodes and edges to them are excluded.
+	///
+	/// This runs on a snapshot of the nodes taken by one traversal, locking each node only once.
+	pub fn summary_outgoing(&self) -> ComponentSummary {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let indices = 0..subgraph.len();
+		ComponentSummary {
+			node_count: subgraph.len(),
+			edge_count: subgraph.edge_count(),
+			source_count: indices
+				.clone()
+				.filter(|&index| subgraph.incoming(index).is_empty())
+				.count(),
+			sink_count: indices
+				.clone()
+				.filter(|&index| subgraph.outgoing(index).is_empty())
+				.count(),
+			has_cycle: subgraph.topological_order().is_err(),
+			max_out_degree: indices.map(|index| subgraph.outgoing(index).len()).max().unwrap_or(0),
+		}
+	}
+
+	/// Summarizes the structure of the nodes reachable via [`Internode::outgoing`] including this node into a checksum, which combines the number of the nodes, the number of their outgoing edges, and the identities of the nodes. Comparing checksums tells whether the structure has changed, except for rare collisions. Changes only to the values other than neighbors aren't reflected.
+	///
+	/// This is computed by one traversal, locking each node only once. The checksum is meaningful only within the same process, as it depends on the addresses of the nodes.
+	pub fn component_checksum_outgoing(&self) -> u64 {
+		let mut ids = Vec::new();
+		let mut edge_count = 0;
+		self.visit(Direction::Outgoing, |node, value| {
+			ids.push(node.as_raw() as usize);
+			edge_count += value.outgoing().count();
+		});
+		ids.sort_unstable();
+		let mut hasher = std::collections::hash_map::DefaultHasher::new();
+		(ids.len(), edge_count, ids).hash(&mut hasher);
+		hasher.finish()
+	}
+
+	/// Collects the nodes reachable via [`Internode::outgoing`] including this node into a set, which is the same as `self.dfs_outgoing().collect()` but cheaper.
+	pub fn reachable_set_outgoing(&self) -> HashSet<Self> { self.reachable(Direction::Outgoing) }
+
+	/// Assigns contiguous indices from `0` to the nodes reachable via [`Internode::outgoing`] including this node, in breadth-first order, e.g. to index into external arrays. Dropped nodes are not indexed, so the result will be empty if this `Internode` is dropped already.
+	pub fn enumerate_outgoing(&self) -> HashMap<Self, usize> {
+		let mut indices = HashMap::new();
+		self.visit(Direction::Outgoing, |node, _| {
+			let index = indices.len();
+			indices.insert(node.clone(), index);
+		});
+		indices
+	}
+
+	/// Collects the nodes reachable via [`Internode::incoming`] including this node into a set, which is the same as `self.dfs_incoming().collect()` but cheaper.
+	pub fn reachable_set_incoming(&self) -> HashSet<Self> { self.reachable(Direction::Incoming) }
+
+	/// Starts a breadth-first search via [`Internode::outgoing`] which yields nodes in batches on demand. See [`Explorer`].
+	pub fn explorer_outgoing(&self) -> Explorer<T> { Explorer::new(self.clone()) }
+
+	/// Performs a depth-first search by recursively calling [`Internode::outgoing_snapshot`]. Includes the starting node first.
+	pub fn dfs_outgoing(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.dfs_outgoing_with(HashSet::new())
+	}
+
+	/// Same as [`Internode::dfs_outgoing`], but tracks visited nodes by the given [`VisitedSet`].
+	pub fn dfs_outgoing_with<'a>(
+		&'a self,
+		mut visited: impl 'a + VisitedSet<T>,
+	) -> impl 'a + FusedIterator<Item = Self> {
 		Gen::new(|co| async move {
 			let mut search = VecDeque::from([self.clone()]);
-			let mut visited = HashSet::new();
 			while let Some(node) = search.pop_front() {
-				if visited.insert(node.clone()) {
+				if visited.insert(&node) {
 					co.yield_(node.clone()).await;
 					let len_old = search.len();
-					search.extend(node.outgoing());
+					search.extend(node.outgoing_snapshot().into_iter().flatten());
 					search.rotate_left(len_old);
 				}
 			}
 		})
 		.into_iter()
+		.fuse()
 	}
 
-	/// Performs a depth-first search by recursively calling [`Internode::incoming`]. Includes the starting node first.
-	pub fn dfs_incoming(&self) -> impl '_ + Iterator<Item = Self> {
+	/// Performs the same depth-first search as [`Internode::dfs_outgoing`], but yields the `(parent, child)` edges of the search tree instead of nodes. Edges to already visited nodes are skipped, so the edges never form a cycle.
+	pub fn spanning_tree_outgoing(&self) -> impl '_ + FusedIterator<Item = (Self, Self)> {
 		Gen::new(|co| async move {
-			let mut search = VecDeque::from([self.clone()]);
 			let mut visited = HashSet::new();
-			while let Some(node) = search.pop_front() {
+			let mut search = VecDeque::from([(None, self.clone())]);
+			while let Some((parent, node)) = search.pop_front() {
 				if visited.insert(node.clone()) {
-					co.yield_(node.clone()).await;
 					let len_old = search.len();
-					search.extend(node.incoming());
+					search.extend(
+						node.outgoing_snapshot()
+							.into_iter()
+							.flatten()
+							.map(|child| (Some(node.clone()), child)),
+					);
 					search.rotate_left(len_old);
+					if let Some(parent) = parent {
+						co.yield_((parent, node)).await;
+					}
 				}
 			}
 		})
 		.into_iter()
+		.fuse()
 	}
 
-	/// Performs a breadth-first search by recursively calling [`Internode::outgoing`]. Includes the starting node first.
-	pub fn bfs_outgoing(&self) -> impl '_ + Iterator<Item = Self> {
+	/// Same as [`Internode::dfs_outgoing`], but visits the neighbors of each node in the order of the keys computed from their values, so that the order is deterministic regardless of the order of [`Neighbors::outgoing`]. Neighbors with equal keys keep their order. Dropped neighbors are skipped.
+	///
+	/// Note that this locks each neighbor once more to compute its key, and sorts the neighbors on every expansion.
+	pub fn dfs_outgoing_stable<'a, K: Ord>(
+		&'a self,
+		key: impl 'a + Fn(&T) -> K,
+	) -> impl 'a + FusedIterator<Item = Self> {
 		Gen::new(|co| async move {
-			let mut search = VecDeque::from([self.clone()]);
 			let mut visited = HashSet::new();
+			let mut search = VecDeque::from([self.clone()]);
 			while let Some(node) = search.pop_front() {
 				if visited.insert(node.clone()) {
+					let mut neighbors = node
+						.outgoing_snapshot()
+						.into_iter()
+						.flatten()
+						.filter_map(|neighbor| {
+							let key = key(&*neighbor.lock()?);
+							Some((key, neighbor))
+						})
+						.collect::<Vec<_>>();
+					neighbors.sort_by(|(a, _), (b, _)| a.cmp(b));
+					co.yield_(node).await;
+					let len_old = search.len();
+					search.extend(neighbors.into_iter().map(|(_, neighbor)| neighbor));
+					search.rotate_left(len_old);
+				}
+			}
+		})
+		.into_iter()
+		.fuse()
+	}
+
+	/// Performs a depth-first search by recursively calling [`Internode::incoming_snapshot`]. Includes the starting node first.
+	pub fn dfs_incoming(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.dfs_incoming_with(HashSet::new())
+	}
+
+	/// Same as [`Internode::dfs_incoming`], but tracks visited nodes by the given [`VisitedSet`].
+	pub fn dfs_incoming_with<'a>(
+		&'a self,
+		mut visited: impl 'a + VisitedSet<T>,
+	) -> impl 'a + FusedIterator<Item = Self> {
+		Gen::new(|co| async move {
+			let mut search = VecDeque::from([self.clone()]);
+			while let Some(node) = search.pop_front() {
+				if visited.insert(&node) {
 					co.yield_(node.clone()).await;
-					search.extend(node.outgoing());
+					let len_old = search.len();
+					search.extend(node.incoming_snapshot().into_iter().flatten());
+					search.rotate_left(len_old);
 				}
 			}
 		})
 		.into_iter()
+		.fuse()
+	}
+
+	/// Performs a depth-first search by recursively calling [`Internode::outgoing_snapshot`], yielding each node only after all the nodes reachable from it, e.g. to tear down dependencies or evaluate expressions leaves first. Includes the starting node last.
+	///
+	/// Within a cycle, a node leading back to a node on the current search path is yielded first, as the latter is regarded as visited already.
+	pub fn dfs_outgoing_postorder(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.dfs_postorder(Direction::Outgoing)
 	}
 
-	/// Performs a breadth-first search by recursively calling [`Internode::incoming`]. Includes the starting node first.
-	pub fn bfs_incoming(&self) -> impl '_ + Iterator<Item = Self> {
+	/// Performs a depth-first search by recursively calling [`Internode::incoming_snapshot`], yielding each node only after all the nodes reachable from it. Includes the starting node last.
+	///
+	/// Within a cycle, a node leading back to a node on the current search path is yielded first, as the latter is regarded as visited already.
+	pub fn dfs_incoming_postorder(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.dfs_postorder(Direction::Incoming)
+	}
+
+	/// Performs a breadth-first search by recursively calling [`Internode::outgoing_snapshot`]. Includes the starting node first.
+	pub fn bfs_outgoing(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.bfs_outgoing_with(HashSet::new())
+	}
+
+	/// Same as [`Internode::bfs_outgoing`], but tracks visited nodes by the given [`VisitedSet`].
+	pub fn bfs_outgoing_with<'a>(
+		&'a self,
+		mut visited: impl 'a + VisitedSet<T>,
+	) -> impl 'a + FusedIterator<Item = Self> {
 		Gen::new(|co| async move {
 			let mut search = VecDeque::from([self.clone()]);
+			while let Some(node) = search.pop_front() {
+				if visited.insert(&node) {
+					co.yield_(node.clone()).await;
+					search.extend(node.outgoing_snapshot().into_iter().flatten());
+				}
+			}
+		})
+		.into_iter()
+		.fuse()
+	}
+
+	/// Same as [`Internode::bfs_outgoing`], but doesn't expand the nodes whose values don't satisfy `should_expand`, i.e. such nodes are yielded but their outgoing neighbors aren't visited through them. Dropped nodes are not expanded either.
+	///
+	/// Each node is locked only once, while `should_expand` is called and its neighbors are collected.
+	pub fn bfs_outgoing_pruned<'a>(
+		&'a self,
+		should_expand: impl 'a + Fn(&T) -> bool,
+	) -> impl 'a + FusedIterator<Item = Self> {
+		Gen::new(|co| async move {
 			let mut visited = HashSet::new();
+			let mut search = VecDeque::from([self.clone()]);
 			while let Some(node) = search.pop_front() {
 				if visited.insert(node.clone()) {
+					let outgoing = node
+						.lock()
+						.filter(|guard| should_expand(guard))
+						.map(|guard| guard.outgoing().collect::<Vec<_>>());
+					co.yield_(node).await;
+					search.extend(outgoing.into_iter().flatten());
+				}
+			}
+		})
+		.into_iter()
+		.fuse()
+	}
+
+	/// Performs the same breadth-first search as [`Internode::bfs_outgoing`] until `budget` elapses, and returns the nodes visited so far along with whether the search completed.
+	///
+	/// The clock is read only once per 64 nodes to amortize the cost, so the search may run a bit longer than `budget`.
+	pub fn bfs_outgoing_timeout(&self, budget: Duration) -> (Vec<Self>, bool) {
+		let deadline = Instant::now() + budget;
+		let mut visited = HashSet::new();
+		let mut nodes = Vec::new();
+		let mut search = VecDeque::from([self.clone()]);
+		while let Some(node) = search.pop_front() {
+			if visited.insert(node.clone()) {
+				if nodes.len() % 64 == 0 && deadline <= Instant::now() {
+					return (nodes, false);
+				}
+				search.extend(node.outgoing_snapshot().into_iter().flatten());
+				nodes.push(node);
+			}
+		}
+		(nodes, true)
+	}
+
+	/// Performs a breadth-first search by recursively calling [`Internode::incoming_snapshot`]. Includes the starting node first.
+	pub fn bfs_incoming(&self) -> impl '_ + FusedIterator<Item = Self> {
+		self.bfs_incoming_with(HashSet::new())
+	}
+
+	/// Same as [`Internode::bfs_incoming`], but tracks visited nodes by the given [`VisitedSet`].
+	pub fn bfs_incoming_with<'a>(
+		&'a self,
+		mut visited: impl 'a + VisitedSet<T>,
+	) -> impl 'a + FusedIterator<Item = Self> {
+		Gen::new(|co| async move {
+			let mut search = VecDeque::from([self.clone()]);
+			while let Some(node) = search.pop_front() {
+				if visited.insert(&node) {
 					co.yield_(node.clone()).await;
-					search.extend(node.incoming());
+					search.extend(node.incoming_snapshot().into_iter().flatten());
 				}
 			}
 		})
 		.into_iter()
+		.fuse()
+	}
+}
+
+impl<T: Neighbors + Clone> Internode<T> {
+	/// Blocks until the internal `Mutex` can be locked and returns a clone of the value. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The lock is released before this returns, so there's no guard to care about, at the cost of cloning the entire value including its neighbors. To read only a part of the value, [`Internode::lock`] is cheaper.
+	pub fn try_value(&self) -> Option<T> { self.lock().map(|guard| guard.clone()) }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Neighbors + Send> Internode<T> {
+	/// Collects [`Neighbors::outgoing`] under the lock like [`Internode::outgoing_snapshot`], and returns a parallel iterator over them. Will be empty if this `Internode` is dropped already.
+	pub fn par_outgoing(&self) -> impl rayon::iter::ParallelIterator<Item = Self> {
+		rayon::iter::IntoParallelIterator::into_par_iter(
+			self.outgoing_snapshot().unwrap_or_default(),
+		)
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl<T: Neighbors + Send + 'static> Internode<T> {
+	/// Same as [`Internode::outgoing_snapshot`], but waits for the lock on the blocking thread pool of `tokio` rather than blocking the current task. No guard is held across `.await` points. Must be called within a `tokio` runtime.
+	///
+	/// Panics in [`Neighbors::outgoing`] are propagated to the caller.
+	pub async fn outgoing_snapshot_async(&self) -> Option<Vec<Self>> {
+		let this = self.clone();
+		match tokio::task::spawn_blocking(move || this.outgoing_snapshot()).await {
+			Ok(neighbors) => neighbors,
+			Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+			Err(_) => None,
+		}
+	}
+
+	/// Same as [`Internode::bfs_outgoing`], but collects the nodes by [`Internode::outgoing_snapshot_async`], so that the current task never blocks on the lock of any node. Includes the starting node first.
+	pub async fn bfs_outgoing_async(&self) -> Vec<Self> {
+		let mut nodes = Vec::new();
+		let mut visited = HashSet::from([self.clone()]);
+		let mut search = VecDeque::from([self.clone()]);
+		while let Some(node) = search.pop_front() {
+			for neighbor in node.outgoing_snapshot_async().await.unwrap_or_default() {
+				if visited.insert(neighbor.clone()) {
+					search.push_back(neighbor);
+				}
+			}
+			nodes.push(node);
+		}
+		nodes
+	}
+}
+
+#[cfg(feature = "rand")]
+impl<T: Neighbors> Internode<T> {
+	/// Samples `k` of [`Neighbors::outgoing`] uniformly at random by reservoir sampling, which iterates over the neighbors only once under the lock without collecting all of them. Returns all the neighbors if there're no more than `k`. Will be empty if this `Internode` is dropped already.
+	///
+	/// The result is deterministic for the same `rng` state and neighbors.
+	pub fn sample_outgoing(&self, k: usize, mut rng: impl rand_core::RngCore) -> Vec<Self> {
+		let Some(guard) = self.lock() else { return Vec::new() };
+		let mut reservoir = Vec::with_capacity(k);
+		for (i, neighbor) in T::outgoing(&guard).enumerate() {
+			if i < k {
+				reservoir.push(neighbor);
+			} else {
+				// Rejects the values in the last incomplete range to avoid modulo bias.
+				let n = i as u64 + 1;
+				let zone = u64::MAX - u64::MAX % n;
+				let j = loop {
+					let x = rng.next_u64();
+					if x < zone {
+						break (x % n) as usize;
+					}
+				};
+				if j < k {
+					reservoir[j] = neighbor;
+				}
+			}
+		}
+		reservoir
+	}
+}
+
+impl<T: Neighbors + Display> Internode<T> {
+	/// Renders the whole connected graph as lines of `id: value -> [ids of outgoing neighbors]`.
+	///
+	/// Ids are assigned in breadth-first order of discovery, following both outgoing and incoming neighbors, starting from `0` for this node. Each node is locked only while its value is formatted and its neighbors are collected, so unlike [`Display`] this doesn't deadlock even if `T`'s [`Display`] implementation visits other nodes on a cycle.
+	pub fn dump(&self) -> String {
+		let mut ids = HashMap::from([(self.clone(), 0)]);
+		let mut search = VecDeque::from([self.clone()]);
+		let mut lines = Vec::new();
+		while let Some(node) = search.pop_front() {
+			let Some(guard) = node.lock() else { continue };
+			let value = guard.to_string();
+			let outgoing = T::outgoing(&guard).collect::<Vec<_>>();
+			let incoming = node.incoming_of(&guard).collect::<Vec<_>>();
+			drop(guard);
+			for neighbor in outgoing.iter().chain(incoming.iter()) {
+				let len = ids.len();
+				if let Entry::Vacant(entry) = ids.entry(neighbor.clone()) {
+					entry.insert(len);
+					search.push_back(neighbor.clone());
+				}
+			}
+			let targets =
+				outgoing.iter().map(|neighbor| ids[neighbor].to_string()).collect::<Vec<_>>();
+			lines.push(format!("{}: {} -> [{}]", ids[&node], value, targets.join(", ")));
+		}
+		lines.join("\n")
+	}
+}
+
+impl<T: Neighbors + Hash> Internode<T> {
+	/// Feeds the structure of the nodes reachable via [`Internode::outgoing`] into `hasher`, i.e. their values and the edges among them, regardless of the identities of the nodes.
+	///
+	/// Nodes are numbered in breadth-first order of discovery starting from `0` for this node, and each node is fed with its value followed by the numbers of its outgoing neighbors in the order of [`Neighbors::outgoing`]. So the hash is stable across runs for graphs constructed the same way. Note that the [`Hash`] implementation of `T` should skip the neighbors, since `Internode` hashes by its identity.
+	pub fn structural_hash_outgoing<H: Hasher>(&self, hasher: &mut H) {
+		let mut ids = HashMap::from([(self.clone(), 0)]);
+		let mut search = VecDeque::from([self.clone()]);
+		while let Some(node) = search.pop_front() {
+			let Some(guard) = node.lock() else {
+				hasher.write_u8(0);
+				continue;
+			};
+			hasher.write_u8(1);
+			T::hash(&guard, hasher);
+			let outgoing = T::outgoing(&guard).collect::<Vec<_>>();
+			drop(guard);
+			hasher.write_usize(outgoing.len());
+			for neighbor in outgoing {
+				let len = ids.len();
+				let id = *ids.entry(neighbor.clone()).or_insert_with(|| {
+					search.push_back(neighbor);
+					len
+				});
+				hasher.write_usize(id);
+			}
+		}
+	}
+}
+
+impl<T: Neighbors + PartialEq> Internode<T> {
+	/// Returns `true` if the nodes reachable via [`Internode::outgoing`] from this node and from `other` have the same structure, i.e. equal values and the same edges among them, regardless of the identities of the nodes.
+	///
+	/// Nodes are matched in breadth-first order of discovery, as in [`Internode::structural_hash_outgoing`]. So this isn't a general isomorphism check, and graphs that are equal but whose [`Neighbors::outgoing`] yield in different orders compare unequal. Each pair of values is compared under their locks, taken as in [`Internode::with_both`].
+	pub fn structurally_eq_outgoing(&self, other: &Self) -> bool {
+		let subgraph = Subgraph::collect(self, Direction::Outgoing, |_| true);
+		let subgraph_other = Subgraph::collect(other, Direction::Outgoing, |_| true);
+		subgraph.len() == subgraph_other.len()
+			&& (0..subgraph.len()).all(|index| {
+				subgraph.outgoing(index) == subgraph_other.outgoing(index)
+					&& subgraph
+						.node(index)
+						.with_both(subgraph_other.node(index), |value, value_other| {
+							value == value_other
+						})
+						.unwrap_or(false)
+			})
+	}
+}
+
+impl<T: Neighbors + Debug> Internode<T> {
+	/// Panics if there's a cycle among the nodes reachable via [`Internode::outgoing`], with the [`Debug`] representation of a node on the cycle. Compiles to nothing unless `debug_assertions` are enabled, just like [`debug_assert!`].
+	///
+	/// See [`Internode::is_dag_outgoing`] for a non-panicking check.
+	pub fn debug_assert_acyclic_outgoing(&self) {
+		#[cfg(debug_assertions)]
+		if let Some(node) = self.find_cycle(Direction::Outgoing) {
+			panic!("found a cycle at {node:?}");
+		}
+	}
+}
+
+impl<T: NeighborsSlice> Internode<T> {
+	/// Blocks until the internal `Mutex` can be locked and calls `f` with [`NeighborsSlice::outgoing_slice`], without cloning the neighbors. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The lock is held while `f` is running, so `f` shouldn't lock this node.
+	pub fn with_outgoing_slice<R>(&self, f: impl FnOnce(&[Self]) -> R) -> Option<R> {
+		self.lock().map(|guard| f(guard.outgoing_slice()))
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and calls `f` with [`NeighborsSlice::incoming_slice`], without cloning the neighbors. Will be `None` if this `Internode` is dropped already.
+	///
+	/// The lock is held while `f` is running, so `f` shouldn't lock this node.
+	pub fn with_incoming_slice<R>(&self, f: impl FnOnce(&[Self]) -> R) -> Option<R> {
+		self.lock().map(|guard| f(guard.incoming_slice()))
+	}
+}
+
+impl<T: NeighborsMut> Internode<T> {
+	/// Severs every node which is connected to this node but not reachable from it via [`Neighbors::outgoing`], so that they can be dropped. See [`prune`] for details.
+	pub fn gc_from_outgoing(&self) { sever_unreachable(std::iter::once(self)) }
+
+	/// Removes parallel edges from [`NeighborsMut::outgoing_mut`] of each node reachable via [`Internode::outgoing`], keeping only the first edge to each neighbor, so that the nodes form a simple graph.
+	///
+	/// Each node is locked only once, like [`Internode::for_each_value_mut_outgoing`]. [`NeighborsMut::incoming_mut`] are left untouched.
+	pub fn simplify_outgoing(&self) {
+		self.for_each_value_mut_outgoing(|value| {
+			let mut targets = HashSet::new();
+			value.outgoing_mut().retain(|target| targets.insert(target.clone()));
+		});
+	}
+
+	/// Adds an edge from this node to `to`, by pushing to [`NeighborsMut::outgoing_mut`] of this node and [`NeighborsMut::incoming_mut`] of `to` at once, so that both directions are kept in sync. Returns `false` if either node is dropped already.
+	pub fn connect(&self, to: &Self) -> bool {
+		let Some((mut guard, guard_to)) = self.lock_both(to) else { return false };
+		guard.outgoing_mut().push(to.clone());
+		guard_to.unwrap_or(guard).incoming_mut().push(self.clone());
+		true
+	}
+
+	/// Replaces [`NeighborsMut::outgoing_mut`] of this node with `outgoing` under the lock, and returns the previous one. Will be `None` if this `Internode` is dropped already.
+	///
+	/// Unlike [`Internode::connect`] and [`Internode::disconnect`], [`NeighborsMut::incoming_mut`] of the neighbors are left untouched. If this node or any of the previous neighbors is no longer connected to any anchored node afterwards, it's dropped.
+	pub fn replace_outgoing(&self, outgoing: Vec<Self>) -> Option<Vec<Self>> {
+		let previous = std::mem::replace(self.lock()?.outgoing_mut(), outgoing);
+		self.release_unless_anchored();
+		for node in &previous {
+			node.release_unless_anchored();
+		}
+		Some(previous)
+	}
+
+	/// Replaces the first occurrence of `old` in [`NeighborsMut::outgoing_mut`] of this node with `new` in place, and also moves this node from [`NeighborsMut::incoming_mut`] of `old` to that of `new`, like [`Internode::disconnect`] and [`Internode::connect`] at once. Returns `false` if there's no such edge or any of the nodes is dropped already.
+	///
+	/// All the nodes are locked at once in the order of their addresses. If either this node or `old` is no longer connected to any anchored node afterwards, it's dropped.
+	pub fn replace_outgoing_target(&self, old: &Self, new: Self) -> bool {
+		let mut nodes = vec![self, old, &new];
+		nodes.sort_by_key(|node| Arc::as_ptr(&node.0));
+		nodes.dedup();
+		let Some(mut guards) = nodes.iter().map(|node| node.lock()).collect::<Option<Vec<_>>>()
+		else {
+			return false;
+		};
+		let position = |node: &Self| nodes.iter().position(|&other| other == node).unwrap();
+		let outgoing = guards[position(self)].outgoing_mut();
+		let Some(index) = outgoing.iter().position(|node| node == old) else { return false };
+		let edge = std::mem::replace(&mut outgoing[index], new.clone());
+		let incoming = guards[position(old)].incoming_mut();
+		let edge_reverse =
+			incoming.iter().position(|node| node == self).map(|index| incoming.remove(index));
+		guards[position(&new)].incoming_mut().push(self.clone());
+		drop(guards);
+		drop((edge, edge_reverse));
+		self.release_unless_anchored();
+		old.release_unless_anchored();
+		true
+	}
+
+	/// Same as [`Internode::connect`], but refuses to add the edge with [`ConnectError::WouldCycle`] if this node is reachable from `to` via [`Internode::outgoing`], i.e. the edge would make a cycle, including a self-loop.
+	///
+	/// Returns [`ConnectError::Dead`] if either end is dead, i.e. this node or `to` is dropped already.
+	///
+	/// Note that the check and the addition aren't atomic, so concurrent edits by other threads may still make a cycle.
+	pub fn try_connect(&self, to: &Self) -> Result<(), ConnectError<T>> {
+		let dead = || ConnectError::Dead { from: self.clone(), to: to.clone() };
+		if !self.is_alive() || !to.is_alive() {
+			return Err(dead());
+		}
+		if to.dfs_outgoing().any(|node| node == *self) {
+			return Err(ConnectError::WouldCycle { from: self.clone(), to: to.clone() });
+		}
+		self.connect(to).then_some(()).ok_or_else(dead)
+	}
+
+	/// Removes an edge from this node to `to` added by [`Internode::connect`], i.e. the first occurrences in both [`NeighborsMut::outgoing_mut`] of this node and [`NeighborsMut::incoming_mut`] of `to`. Returns `false` if there's no such edge.
+	///
+	/// If either node is no longer connected to any anchored node afterwards, it's dropped.
+	pub fn disconnect(&self, to: &Self) -> bool {
+		let Some((mut guard, guard_to)) = self.lock_both(to) else { return false };
+		let Some(index) = guard.outgoing_mut().iter().position(|node| node == to) else {
+			return false;
+		};
+		let edge = guard.outgoing_mut().remove(index);
+		let mut guard_to = guard_to.unwrap_or(guard);
+		let index = guard_to.incoming_mut().iter().position(|node| node == self);
+		let edge_reverse = index.map(|index| guard_to.incoming_mut().remove(index));
+		drop(guard_to);
+		drop((edge, edge_reverse));
+		self.release_unless_anchored();
+		to.release_unless_anchored();
+		true
+	}
+}
+
+impl<T: IndexedNeighbors> Internode<T> {
+	/// Adds an edge from this node to `to`, by pushing to [`IndexedNeighbors::outgoing_mut`] of this node and recording this node as an incoming neighbor of `to` in the index maintained by the crate. Returns `false` if either node is dropped already.
+	///
+	/// The record is weak, and it's cleared when `to` is dropped.
+	pub fn connect_indexed(&self, to: &Self) -> bool {
+		let Some((mut guard, guard_to)) = self.lock_both(to) else { return false };
+		guard.outgoing_mut().push(to.clone());
+		to.0.predecessors.lock().unwrap().push(self.downgrade_weak());
+		drop((guard, guard_to));
+		true
+	}
+
+	/// Removes an edge from this node to `to` added by [`Internode::connect_indexed`], i.e. the first occurrence in [`IndexedNeighbors::outgoing_mut`] of this node along with its record in the index. Returns `false` if there's no such edge.
+	///
+	/// If either node is no longer connected to any anchored node afterwards, it's dropped.
+	pub fn disconnect_indexed(&self, to: &Self) -> bool {
+		let Some((mut guard, guard_to)) = self.lock_both(to) else { return false };
+		let Some(index) = guard.outgoing_mut().iter().position(|node| node == to) else {
+			return false;
+		};
+		let edge = guard.outgoing_mut().remove(index);
+		let mut predecessors = to.0.predecessors.lock().unwrap();
+		if let Some(index) =
+			predecessors.iter().position(|weak| weak.as_ptr() == Arc::as_ptr(&self.0))
+		{
+			predecessors.remove(index);
+		}
+		drop(predecessors);
+		drop((guard, guard_to));
+		drop(edge);
+		self.release_unless_anchored();
+		to.release_unless_anchored();
+		true
 	}
 }
 
@@ -151,6 +1708,14 @@ impl<T: Neighbors> PartialEq for Internode<T> {
 
 impl<T: Neighbors> Eq for Internode<T> {}
 
+impl<T: Neighbors> PartialOrd for Internode<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl<T: Neighbors> Ord for Internode<T> {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.0.id.cmp(&other.0.id) }
+}
+
 impl<T: Neighbors> Hash for Internode<T> {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) { Arc::as_ptr(&self.0).hash(state) }
 }
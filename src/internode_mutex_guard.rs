@@ -2,22 +2,36 @@ use std::fmt::Display;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ptr::NonNull;
-use std::sync::MutexGuard;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
 use super::*;
 
 /// A special mutex guard for the inner value of a node.
 ///
-/// Implements [`Deref`] and [`DerefMut`], so users can think of this as just [`MutexGuard<T>`].
+/// Implements [`Deref`] and [`DerefMut`], so users can think of this as just [`MutexGuard<T>`](std::sync::MutexGuard).
 ///
 /// Returned by [`Node::lock`] and [`Internode::lock`].
 #[derive(Debug)]
 pub struct InternodeMutexGuard<'a, T: Neighbors> {
-	guard: MutexGuard<'a, Option<T>>,
+	guard: ValueMutexGuard<'a, Option<T>>,
+	generation: &'a AtomicU64,
 }
 
 impl<'a, T: Neighbors> InternodeMutexGuard<'a, T> {
-	pub(crate) fn new(guard: MutexGuard<'a, Option<T>>) -> Self { Self { guard } }
+	pub(crate) fn new(guard: ValueMutexGuard<'a, Option<T>>, generation: &'a AtomicU64) -> Self {
+		Self { guard, generation }
+	}
+
+	/// Makes a guard for a part of the value, e.g. a field. The node is kept locked as long as the returned guard exists.
+	///
+	/// Mapping alone doesn't count as a change to the value; the change is counted when the returned guard is mutably dereferenced, so `f` is expected only to project the part rather than edit it.
+	pub fn map<U: ?Sized>(
+		self,
+		f: impl FnOnce(&mut T) -> &mut U,
+	) -> MappedInternodeMutexGuard<'a, T, U> {
+		MappedInternodeMutexGuard::new(self.guard, self.generation, f)
+	}
 
 	pub fn outgoing(self) -> impl 'a + Iterator<Item = Internode<T>> {
 		InternodeMutexGuardIterOutgoing::new(self.guard)
@@ -34,7 +48,10 @@ impl<'a, T: Neighbors> Deref for InternodeMutexGuard<'a, T> {
 }
 
 impl<'a, T: Neighbors> DerefMut for InternodeMutexGuard<'a, T> {
-	fn deref_mut(&mut self) -> &mut Self::Target { self.guard.as_mut().unwrap() }
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.generation.fetch_add(1, Ordering::Release);
+		self.guard.as_mut().unwrap()
+	}
 }
 
 impl<'a, T: Neighbors + Display> Display for InternodeMutexGuard<'a, T> {
@@ -44,38 +61,42 @@ impl<'a, T: Neighbors + Display> Display for InternodeMutexGuard<'a, T> {
 	}
 }
 
+// `iter` borrows the value behind `_guard`, so it must be declared first to be dropped before the lock is released.
 struct InternodeMutexGuardIterOutgoing<'a, T: Neighbors> {
-	guard: MutexGuard<'a, Option<T>>,
 	iter: <T as Neighbors>::Iter<'a>,
+	_guard: ValueMutexGuard<'a, Option<T>>,
 }
 
 impl<'a, T: Neighbors> InternodeMutexGuardIterOutgoing<'a, T> {
-	pub fn new(mut guard: MutexGuard<'a, Option<T>>) -> Self {
+	pub fn new(mut guard: ValueMutexGuard<'a, Option<T>>) -> Self {
 		let value = unsafe { NonNull::new_unchecked(guard.as_mut().unwrap() as *mut T).as_ref() };
 		let iter = value.outgoing();
-		Self { guard, iter }
+		Self { iter, _guard: guard }
 	}
 }
 
 impl<'a, T: Neighbors> Iterator for InternodeMutexGuardIterOutgoing<'a, T> {
 	type Item = Internode<T>;
 	fn next(&mut self) -> Option<Self::Item> { self.iter.next() }
+	fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
 }
 
+// `iter` borrows the value behind `_guard`, so it must be declared first to be dropped before the lock is released.
 struct InternodeMutexGuardIterIncoming<'a, T: Neighbors> {
-	guard: MutexGuard<'a, Option<T>>,
 	iter: <T as Neighbors>::Iter<'a>,
+	_guard: ValueMutexGuard<'a, Option<T>>,
 }
 
 impl<'a, T: Neighbors> InternodeMutexGuardIterIncoming<'a, T> {
-	pub fn new(mut guard: MutexGuard<'a, Option<T>>) -> Self {
+	pub fn new(mut guard: ValueMutexGuard<'a, Option<T>>) -> Self {
 		let value = unsafe { NonNull::new_unchecked(guard.as_mut().unwrap() as *mut T).as_ref() };
 		let iter = value.incoming();
-		Self { guard, iter }
+		Self { iter, _guard: guard }
 	}
 }
 
 impl<'a, T: Neighbors> Iterator for InternodeMutexGuardIterIncoming<'a, T> {
 	type Item = Internode<T>;
 	fn next(&mut self) -> Option<Self::Item> { self.iter.next() }
+	fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-#![doc = include_str!("../README.md")]
+#![cfg_attr(not(doctest), doc = include_str!("../README.md"))]
 
 mod node;
 pub use self::node::*;
@@ -6,11 +6,62 @@ pub use self::node::*;
 mod anchor;
 pub(crate) use self::anchor::*;
 
+mod value_mutex;
+pub(crate) use self::value_mutex::*;
+
 mod internode;
 pub use self::internode::*;
 
+mod cached_internode;
+pub use self::cached_internode::*;
+
 mod internode_mutex_guard;
 pub use self::internode_mutex_guard::*;
 
+mod mapped_internode_mutex_guard;
+pub use self::mapped_internode_mutex_guard::*;
+
+mod tracked_internode_mutex_guard;
+pub use self::tracked_internode_mutex_guard::*;
+
+mod cycle_error;
+pub use self::cycle_error::*;
+
+mod connect_error;
+pub use self::connect_error::*;
+
+mod lock_status;
+pub use self::lock_status::*;
+
+mod component_summary;
+pub use self::component_summary::*;
+
+mod interner;
+pub use self::interner::*;
+
+mod component_handle;
+pub use self::component_handle::*;
+
 mod neighbors;
-pub use self::neighbors::*;
\ No newline at end of file
+pub use self::neighbors::*;
+
+#[cfg(feature = "derive")]
+pub use internode_derive::Neighbors;
+
+mod symmetric;
+pub use self::symmetric::*;
+
+mod visited_set;
+pub use self::visited_set::*;
+
+mod subgraph;
+pub use self::subgraph::*;
+
+mod prune;
+pub use self::prune::*;
+
+mod explorer;
+pub use self::explorer::*;
+
+mod bfs_outgoing_from_many;
+pub use self::bfs_outgoing_from_many::*;
\ No newline at end of file
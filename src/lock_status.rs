@@ -0,0 +1,28 @@
+use std::error::Error;
+use std::fmt::Display;
+
+/// The reason why a node couldn't be locked.
+///
+/// Returned by [`Internode::lock_timeout_ms`](crate::Internode::lock_timeout_ms).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockStatus {
+	/// The node is dropped already.
+	Dead,
+	/// The node is still locked by someone else after the timeout.
+	TimedOut,
+	/// Someone else panicked while holding the lock.
+	Poisoned,
+}
+
+impl Display for LockStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Dead => write!(f, "node is dropped already"),
+			Self::TimedOut => write!(f, "timed out while waiting for lock"),
+			Self::Poisoned => write!(f, "lock is poisoned"),
+		}
+	}
+}
+
+impl Error for LockStatus {}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::ptr::NonNull;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use super::*;
+
+/// A mutex guard for a part of the inner value of a node.
+///
+/// Implements [`Deref`] and [`DerefMut`] to `U`, while the whole node is kept locked.
+///
+/// Returned by [`InternodeMutexGuard::map`].
+///
+/// Like `MappedMutexGuard` of the standard library, this is invariant in `U`, so that a value with a shorter lifetime can't be written through it:
+///
+/// ```compile_fail
+/// use internode::MappedInternodeMutexGuard;
+/// use internode::Neighbors;
+///
+/// fn shorten<'a, 'b, T: Neighbors>(
+///     guard: MappedInternodeMutexGuard<'a, T, &'static str>,
+/// ) -> MappedInternodeMutexGuard<'a, T, &'b str> {
+///     guard
+/// }
+/// ```
+pub struct MappedInternodeMutexGuard<'a, T: Neighbors, U: ?Sized> {
+	// Points to a part of the value behind `_guard`, which stays locked as long as this exists.
+	value: NonNull<U>,
+	_guard: ValueMutexGuard<'a, Option<T>>,
+	generation: &'a AtomicU64,
+	// `NonNull<U>` alone is covariant in `U`, which would allow writing a shorter-lived value through `DerefMut`.
+	_marker: PhantomData<&'a mut U>,
+}
+
+impl<'a, T: Neighbors, U: ?Sized> MappedInternodeMutexGuard<'a, T, U> {
+	pub(crate) fn new(
+		mut guard: ValueMutexGuard<'a, Option<T>>,
+		generation: &'a AtomicU64,
+		f: impl FnOnce(&mut T) -> &mut U,
+	) -> Self {
+		let value = NonNull::from(f(guard.as_mut().unwrap()));
+		Self { value, _guard: guard, generation, _marker: PhantomData }
+	}
+}
+
+impl<'a, T: Neighbors, U: ?Sized> Deref for MappedInternodeMutexGuard<'a, T, U> {
+	type Target = U;
+	fn deref(&self) -> &Self::Target { unsafe { self.value.as_ref() } }
+}
+
+impl<'a, T: Neighbors, U: ?Sized> DerefMut for MappedInternodeMutexGuard<'a, T, U> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.generation.fetch_add(1, Ordering::Release);
+		unsafe { self.value.as_mut() }
+	}
+}
+
+impl<'a, T: Neighbors, U: ?Sized + Debug> Debug for MappedInternodeMutexGuard<'a, T, U> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Debug::fmt(&**self, f)?;
+		Ok(())
+	}
+}
+
+impl<'a, T: Neighbors, U: ?Sized + Display> Display for MappedInternodeMutexGuard<'a, T, U> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&**self, f)?;
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -10,4 +10,30 @@ pub trait Neighbors: Sized {
 
 	/// Returns an iterator over the incoming neighbors of this node.
 	fn incoming(&self) -> Self::Iter<'_>;
+}
+
+/// Provides mutable access to neighbors of a node, which is required by methods that edit edges.
+pub trait NeighborsMut: Neighbors {
+	/// Returns the outgoing neighbors of this node.
+	fn outgoing_mut(&mut self) -> &mut Vec<Internode<Self>>;
+
+	/// Returns the incoming neighbors of this node.
+	fn incoming_mut(&mut self) -> &mut Vec<Internode<Self>>;
+}
+
+/// Provides access to neighbors of a node stored contiguously, which allows reading them without cloning.
+pub trait NeighborsSlice: Neighbors {
+	/// Returns the outgoing neighbors of this node.
+	fn outgoing_slice(&self) -> &[Internode<Self>];
+
+	/// Returns the incoming neighbors of this node.
+	fn incoming_slice(&self) -> &[Internode<Self>];
+}
+
+/// Provides mutable access to the outgoing neighbors of a node, for values which don't hold their incoming neighbors.
+///
+/// Instead, the incoming neighbors are recorded by the crate as long as edges are edited only through [`Internode::connect_indexed`] and [`Internode::disconnect_indexed`], and they're followed by [`Internode::incoming`] and traversals in addition to [`Neighbors::incoming`], which can be left empty.
+pub trait IndexedNeighbors: Neighbors {
+	/// Returns the outgoing neighbors of this node.
+	fn outgoing_mut(&mut self) -> &mut Vec<Internode<Self>>;
 }
\ No newline at end of file
@@ -1,8 +1,13 @@
 use super::*;
+use genawaiter::sync::Gen;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::iter::FusedIterator;
 use std::ops::Deref;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 /// An owning shared reference to a node.
@@ -19,19 +24,89 @@ impl<T: Neighbors> Node<T> {
 		Self { anchor: Anchor::new(inner) }
 	}
 
+	pub(crate) fn from_anchor(anchor: Arc<Anchor<T>>) -> Self { Self { anchor } }
+
 	/// Creates a new `Node` with the given value.
 	pub fn new(value: T) -> Self { Self::from_internode(Internode::new(value)) }
 
+	/// Creates a new `Node` with the value returned by `f`, which receives the `Node` itself.
+	///
+	/// This is useful for registering the `Node` somewhere or making self-referencing edges. Note that the value is not set yet while `f` is running, so locking the `Node` there panics, and [`Internode::lock`] returns `None`.
+	pub fn new_with(f: impl FnOnce(&Self) -> T) -> Self {
+		let node = Self::from_internode(Internode::from_value(None));
+		let value = f(&node);
+		let mut guard = node.value().lock().unwrap();
+		node.generation().fetch_add(1, Ordering::Release);
+		guard.replace(value);
+		drop(guard);
+		node
+	}
+
 	/// Downgrades this `Node` into an `Internode`.
 	pub fn downgrade(&self) -> Internode<T> { self.anchor.inner().clone() }
 
 	/// Blocks until the internal `Mutex` can be locked and returns a guard to the value.
-	pub fn lock(&self) -> InternodeMutexGuard<'_, T> { self.anchor.inner().lock().unwrap() }
+	///
+	/// Panics if the value is missing, i.e. while it's not set yet in [`Node::new_with`], or after it's lost by a panic in [`Internode::update`].
+	pub fn lock(&self) -> InternodeMutexGuard<'_, T> {
+		self.anchor.inner().lock().expect(
+			"the value is missing, either not set yet during `Node::new_with` or lost by a panic in `Internode::update`",
+		)
+	}
+
+	/// Blocks until the internal `Mutex` can be locked and replaces the value, returning the old one.
+	///
+	/// Note that the neighbors are replaced as well as the rest of the value, as they're a part of it.
+	pub fn replace_value(&self, value: T) -> T { std::mem::replace(&mut *self.lock(), value) }
+
+	/// Tears down the connected graph, and returns the values of the nodes reachable via [`Internode::outgoing`] in breadth-first order. The values of other connected nodes are dropped.
+	///
+	/// This fails and gives back the `Node` if any other `Node` to the connected nodes is held elsewhere. Afterwards, all the `Internode`s to the connected nodes, including ones held by the returned values, are dropped already and can't be upgraded.
+	pub fn drain_component_outgoing(self) -> Result<Vec<T>, Self> {
+		let mut connected = Vec::new();
+		self.visit(Direction::Both, |node, _| connected.push(node.clone()));
+		if Arc::strong_count(&self.anchor) > 1
+			|| connected.iter().any(|node| node != self.anchor.inner() && node.is_anchored())
+		{
+			return Err(self);
+		}
+		let mut reachable = Vec::new();
+		self.visit(Direction::Outgoing, |node, _| reachable.push(node.clone()));
+		let values = reachable.iter().filter_map(Internode::take_value).collect();
+		drop(connected.iter().filter_map(Internode::take_value).collect::<Vec<_>>());
+		Ok(values)
+	}
+
+	/// Performs the same depth-first search as [`Internode::dfs_outgoing`], but takes the ownership of this `Node`, so that the connected graph isn't dropped during the traversal even if all other `Node`s are. Every yielded node is alive at the time it's yielded.
+	///
+	/// Note that the whole connected graph is kept in the memory until the iterator is exhausted or dropped.
+	pub fn dfs_outgoing_owned(self) -> impl FusedIterator<Item = Internode<T>> {
+		Gen::new(|co| async move {
+			let mut visited = HashSet::new();
+			let mut search = VecDeque::from([self.downgrade()]);
+			while let Some(node) = search.pop_front() {
+				if visited.insert(node.clone()) {
+					let Some(outgoing) = node.outgoing_snapshot() else { continue };
+					let len_old = search.len();
+					search.extend(outgoing);
+					search.rotate_left(len_old);
+					co.yield_(node).await;
+				}
+			}
+		})
+		.into_iter()
+		.fuse()
+	}
+
+	/// Converts this `Node` into a [`ComponentHandle`], releasing the ownership.
+	pub fn into_handle(self) -> ComponentHandle<T> {
+		ComponentHandle::new(Arc::downgrade(&self.anchor), self.downgrade_weak())
+	}
 }
 
 impl<T: Neighbors> Deref for Node<T> {
 	type Target = Internode<T>;
-	fn deref(&self) -> &Self::Target { &self.anchor.inner() }
+	fn deref(&self) -> &Self::Target { self.anchor.inner() }
 }
 
 impl<T: Neighbors> Clone for Node<T> {
@@ -46,6 +121,16 @@ impl<T: Neighbors> PartialEq for Node<T> {
 
 impl<T: Neighbors> Eq for Node<T> {}
 
+impl<T: Neighbors> PartialOrd for Node<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl<T: Neighbors> Ord for Node<T> {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		Ord::cmp(&self.anchor.inner(), &other.anchor.inner())
+	}
+}
+
 impl<T: Neighbors> Hash for Node<T> {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) { Hash::hash(&self.anchor.inner(), state); }
 }
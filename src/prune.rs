@@ -0,0 +1,40 @@
+use super::*;
+use std::collections::HashSet;
+
+/// Severs every node which is connected to any of `roots` but not reachable from them via [`Neighbors::outgoing`].
+///
+/// Since a `Node` anchors the entire connected graph, nodes which became unreachable from the roots after edits are still kept alive as long as they are connected in either direction. This removes all edges from and to such nodes, then drops them unless they are anchored by `Node`s held elsewhere, in which case they are left as isolated nodes.
+pub fn prune<T: NeighborsMut>(roots: &[Node<T>]) {
+	sever_unreachable(roots.iter().map(|root| &**root))
+}
+
+pub(crate) fn sever_unreachable<'a, T: 'a + NeighborsMut>(
+	roots: impl Iterator<Item = &'a Internode<T>>,
+) {
+	let mut reachable = HashSet::new();
+	let mut connected = HashSet::new();
+	for root in roots {
+		root.visit(Direction::Outgoing, |node, _| {
+			reachable.insert(node.clone());
+		});
+		root.visit(Direction::Both, |node, _| {
+			connected.insert(node.clone());
+		});
+	}
+	let unreachable = connected.difference(&reachable).collect::<Vec<_>>();
+	for node in &connected {
+		let Some(mut guard) = node.lock() else { continue };
+		if reachable.contains(node) {
+			guard.outgoing_mut().retain(|neighbor| reachable.contains(neighbor));
+			guard.incoming_mut().retain(|neighbor| reachable.contains(neighbor));
+		} else {
+			let edges =
+				(std::mem::take(guard.outgoing_mut()), std::mem::take(guard.incoming_mut()));
+			drop(guard);
+			drop(edges);
+		}
+	}
+	for node in unreachable {
+		node.release_unless_anchored();
+	}
+}
\ No newline at end of file
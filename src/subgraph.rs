@@ -0,0 +1,154 @@
+use super::*;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// A snapshot of a part of a graph, which can be analyzed without locking any node.
+///
+/// Nodes are numbered by indices in the order they were collected, and edges are held as lists of these indices. Parallel edges and self-loops are preserved. The original nodes can be looked up by [`Subgraph::node`] and [`Subgraph::index_of`].
+///
+/// Returned by [`Internode::subgraph_filtered_outgoing`].
+pub struct Subgraph<T: Neighbors> {
+	nodes: Vec<Internode<T>>,
+	indices: HashMap<Internode<T>, usize>,
+	outgoing: Vec<Vec<usize>>,
+	incoming: Vec<Vec<usize>>,
+}
+
+impl<T: Neighbors> Subgraph<T> {
+	/// Collects the live nodes reachable from `start` in the given direction, keeping only ones whose value satisfies `pred` along with the outgoing edges among them. Each node is locked only once.
+	pub(crate) fn collect(
+		start: &Internode<T>,
+		direction: Direction,
+		pred: impl Fn(&T) -> bool,
+	) -> Self {
+		let mut adjacency = Vec::new();
+		start.visit(direction, |node, value| {
+			if pred(value) {
+				adjacency.push((node.clone(), value.outgoing().collect::<Vec<_>>()));
+			}
+		});
+		let indices = adjacency
+			.iter()
+			.enumerate()
+			.map(|(index, (node, _))| (node.clone(), index))
+			.collect::<HashMap<_, _>>();
+		let mut outgoing = vec![Vec::new(); adjacency.len()];
+		let mut incoming = vec![Vec::new(); adjacency.len()];
+		for (from, (_, targets)) in adjacency.iter().enumerate() {
+			for to in targets.iter().filter_map(|target| indices.get(target).copied()) {
+				outgoing[from].push(to);
+				incoming[to].push(from);
+			}
+		}
+		let nodes = adjacency.into_iter().map(|(node, _)| node).collect();
+		Self { nodes, indices, outgoing, incoming }
+	}
+
+	/// Returns the number of nodes.
+	pub fn len(&self) -> usize { self.nodes.len() }
+
+	/// Returns `true` if there's no node.
+	pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+
+	/// Returns the number of edges.
+	pub fn edge_count(&self) -> usize { self.outgoing.iter().map(Vec::len).sum() }
+
+	/// Returns all the original nodes, ordered by their indices.
+	pub fn nodes(&self) -> &[Internode<T>] { &self.nodes }
+
+	/// Returns the original node at the given index.
+	pub fn node(&self, index: usize) -> &Internode<T> { &self.nodes[index] }
+
+	/// Returns the index of the given original node, if it's contained.
+	pub fn index_of(&self, node: &Internode<T>) -> Option<usize> { self.indices.get(node).copied() }
+
+	/// Returns the indices of the outgoing neighbors of the node at the given index.
+	pub fn outgoing(&self, index: usize) -> &[usize] { &self.outgoing[index] }
+
+	/// Returns the indices of the incoming neighbors of the node at the given index.
+	pub fn incoming(&self, index: usize) -> &[usize] { &self.incoming[index] }
+
+	/// Sorts the indices topologically by Kahn's algorithm, i.e. every node comes before its outgoing neighbors. On failure, returns the index of a node on a cycle.
+	pub(crate) fn topological_order(&self) -> Result<Vec<usize>, usize> {
+		let mut degrees = self.incoming.iter().map(Vec::len).collect::<Vec<_>>();
+		let mut search =
+			(0..self.len()).filter(|&index| degrees[index] == 0).collect::<VecDeque<_>>();
+		let mut order = Vec::with_capacity(self.len());
+		while let Some(index) = search.pop_front() {
+			order.push(index);
+			for &neighbor in &self.outgoing[index] {
+				degrees[neighbor] -= 1;
+				if degrees[neighbor] == 0 {
+					search.push_back(neighbor);
+				}
+			}
+		}
+		if order.len() == self.len() {
+			return Ok(order);
+		}
+		// Every remaining node has a remaining incoming neighbor, so walking them backwards reaches a cycle.
+		let mut visited = vec![false; self.len()];
+		let mut index = (0..self.len()).find(|&index| degrees[index] > 0).unwrap();
+		while !std::mem::replace(&mut visited[index], true) {
+			index = self.incoming[index].iter().copied().find(|&from| degrees[from] > 0).unwrap();
+		}
+		Err(index)
+	}
+
+	/// Computes the length of the longest path to each node from any node having no incoming neighbors, ordered by indices. On failure, returns the index of a node on a cycle.
+	pub(crate) fn longest_path_depths(&self) -> Result<Vec<usize>, usize> {
+		let mut depths = vec![0; self.len()];
+		for index in self.topological_order()? {
+			for &neighbor in &self.outgoing[index] {
+				depths[neighbor] = depths[neighbor].max(depths[index] + 1);
+			}
+		}
+		Ok(depths)
+	}
+
+	/// Performs a depth-first search from the node at index `0` regarding edges as undirected (see [`Subgraph::undirected`]), and returns the discovery time, the low-link, and the parent in the search tree of each node, ordered by indices. Nodes not reached get `usize::MAX` as the discovery time.
+	pub(crate) fn low_links(&self) -> (Vec<usize>, Vec<usize>, Vec<Option<usize>>) {
+		let mut times = vec![usize::MAX; self.len()];
+		let mut lows = vec![usize::MAX; self.len()];
+		let mut parents = vec![None; self.len()];
+		if self.is_empty() {
+			return (times, lows, parents);
+		}
+		let mut time = 0;
+		(times[0], lows[0]) = (time, time);
+		let mut stack = vec![(0, self.undirected(0), 0)];
+		while let Some((index, neighbors, position)) = stack.last_mut() {
+			let index = *index;
+			if let Some(&neighbor) = neighbors.get(*position) {
+				*position += 1;
+				if times[neighbor] == usize::MAX {
+					time += 1;
+					(times[neighbor], lows[neighbor]) = (time, time);
+					parents[neighbor] = Some(index);
+					stack.push((neighbor, self.undirected(neighbor), 0));
+				} else if parents[index] != Some(neighbor) {
+					lows[index] = lows[index].min(times[neighbor]);
+				}
+			} else {
+				stack.pop();
+				if let Some(parent) = parents[index] {
+					lows[parent] = lows[parent].min(lows[index]);
+				}
+			}
+		}
+		(times, lows, parents)
+	}
+
+	/// Returns the sorted indices of the distinct neighbors of the node at the given index, regarding edges as undirected. Self-loops are ignored.
+	pub fn undirected(&self, index: usize) -> Vec<usize> {
+		let mut neighbors = self.outgoing[index]
+			.iter()
+			.chain(&self.incoming[index])
+			.copied()
+			.filter(|&neighbor| neighbor != index)
+			.collect::<Vec<_>>();
+		neighbors.sort_unstable();
+		neighbors.dedup();
+		neighbors
+	}
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+use super::*;
+
+/// A pair of outgoing and incoming neighbors, which can be embedded into a node value to implement [`Neighbors`] and [`NeighborsMut`] via [`SymmetricNeighbors`].
+///
+/// Edges are meant to be edited only through [`Internode::connect`] and [`Internode::disconnect`], which keep both directions in sync, so there's no need to maintain incoming neighbors by hand.
+pub struct Symmetric<T: Neighbors> {
+	outgoing: Vec<Internode<T>>,
+	incoming: Vec<Internode<T>>,
+}
+
+impl<T: Neighbors> Symmetric<T> {
+	/// Creates a new `Symmetric` without any neighbor.
+	pub fn new() -> Self { Self { outgoing: Vec::new(), incoming: Vec::new() } }
+
+	/// Returns the outgoing neighbors.
+	pub fn outgoing(&self) -> &[Internode<T>] { &self.outgoing }
+
+	/// Returns the incoming neighbors.
+	pub fn incoming(&self) -> &[Internode<T>] { &self.incoming }
+}
+
+impl<T: Neighbors> Default for Symmetric<T> {
+	fn default() -> Self { Self::new() }
+}
+
+/// Implements [`Neighbors`] and [`NeighborsMut`] by delegating to an embedded [`Symmetric`].
+pub trait SymmetricNeighbors: Sized {
+	/// Returns the embedded [`Symmetric`].
+	fn symmetric(&self) -> &Symmetric<Self>
+	where Self: Neighbors;
+
+	/// Returns the embedded [`Symmetric`] mutably.
+	fn symmetric_mut(&mut self) -> &mut Symmetric<Self>
+	where Self: Neighbors;
+}
+
+impl<T: SymmetricNeighbors> Neighbors for T {
+	type Iter<'a>
+		= std::iter::Cloned<std::slice::Iter<'a, Internode<Self>>>
+	where Self: 'a;
+	fn outgoing(&self) -> Self::Iter<'_> { self.symmetric().outgoing.iter().cloned() }
+	fn incoming(&self) -> Self::Iter<'_> { self.symmetric().incoming.iter().cloned() }
+}
+
+impl<T: SymmetricNeighbors> NeighborsMut for T {
+	fn outgoing_mut(&mut self) -> &mut Vec<Internode<Self>> { &mut self.symmetric_mut().outgoing }
+	fn incoming_mut(&mut self) -> &mut Vec<Internode<Self>> { &mut self.symmetric_mut().incoming }
+}
\ No newline at end of file
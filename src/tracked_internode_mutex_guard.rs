@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use super::*;
+
+/// A mutex guard for the inner value of a node, which drops the nodes disconnected by edits to the value when it's dropped.
+///
+/// Implements [`Deref`] and [`DerefMut`], just like [`InternodeMutexGuard`].
+///
+/// Returned by [`Internode::lock_tracked`].
+pub struct TrackedInternodeMutexGuard<'a, T: Neighbors> {
+	node: &'a Internode<T>,
+	guard: Option<InternodeMutexGuard<'a, T>>,
+	neighbors: Vec<Internode<T>>,
+}
+
+impl<'a, T: Neighbors> TrackedInternodeMutexGuard<'a, T> {
+	pub(crate) fn new(node: &'a Internode<T>, guard: InternodeMutexGuard<'a, T>) -> Self {
+		let neighbors = Direction::Both.neighbors(node, &*guard);
+		Self { node, guard: Some(guard), neighbors }
+	}
+}
+
+impl<'a, T: Neighbors> Deref for TrackedInternodeMutexGuard<'a, T> {
+	type Target = T;
+	fn deref(&self) -> &Self::Target { self.guard.as_ref().unwrap() }
+}
+
+impl<'a, T: Neighbors> DerefMut for TrackedInternodeMutexGuard<'a, T> {
+	fn deref_mut(&mut self) -> &mut Self::Target { self.guard.as_mut().unwrap() }
+}
+
+impl<'a, T: Neighbors> Drop for TrackedInternodeMutexGuard<'a, T> {
+	fn drop(&mut self) {
+		let Some(guard) = self.guard.take() else { return };
+		let neighbors =
+			Direction::Both.neighbors(self.node, &*guard).into_iter().collect::<HashSet<_>>();
+		drop(guard);
+		let removed = self
+			.neighbors
+			.iter()
+			.filter(|neighbor| !neighbors.contains(neighbor))
+			.collect::<Vec<_>>();
+		if !removed.is_empty() {
+			self.node.release_unless_anchored();
+			for neighbor in removed {
+				neighbor.release_unless_anchored();
+			}
+		}
+	}
+}
+
+impl<'a, T: Neighbors + Debug> Debug for TrackedInternodeMutexGuard<'a, T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Debug::fmt(&**self, f)?;
+		Ok(())
+	}
+}
+
+impl<'a, T: Neighbors + Display> Display for TrackedInternodeMutexGuard<'a, T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&**self, f)?;
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,60 @@
+use std::sync::LockResult;
+use std::sync::TryLockError;
+use std::sync::TryLockResult;
+use std::time::Duration;
+#[cfg(not(feature = "parking_lot"))]
+use std::time::Instant;
+
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) type ValueMutexGuard<'a, T> = std::sync::MutexGuard<'a, T>;
+
+#[cfg(feature = "parking_lot")]
+pub(crate) type ValueMutexGuard<'a, T> = parking_lot::FairMutexGuard<'a, T>;
+
+/// The `Mutex` guarding the value of a node, which is [`std::sync::Mutex`] by default, or `parking_lot::FairMutex` if the `parking_lot` feature is enabled.
+///
+/// This mirrors the API of [`std::sync::Mutex`], so the `parking_lot` variant never reports poisoning.
+#[derive(Default)]
+pub(crate) struct ValueMutex<T> {
+	#[cfg(not(feature = "parking_lot"))]
+	inner: std::sync::Mutex<T>,
+	#[cfg(feature = "parking_lot")]
+	inner: parking_lot::FairMutex<T>,
+}
+
+impl<T> ValueMutex<T> {
+	#[cfg(not(feature = "parking_lot"))]
+	pub(crate) fn new(value: T) -> Self { Self { inner: std::sync::Mutex::new(value) } }
+
+	#[cfg(feature = "parking_lot")]
+	pub(crate) fn new(value: T) -> Self { Self { inner: parking_lot::FairMutex::new(value) } }
+
+	#[cfg(not(feature = "parking_lot"))]
+	pub(crate) fn lock(&self) -> LockResult<ValueMutexGuard<'_, T>> { self.inner.lock() }
+
+	#[cfg(feature = "parking_lot")]
+	pub(crate) fn lock(&self) -> LockResult<ValueMutexGuard<'_, T>> { Ok(self.inner.lock()) }
+
+	// `std::sync::Mutex` has no timed locking, so this polls `try_lock` with short sleeps until the deadline.
+	#[cfg(not(feature = "parking_lot"))]
+	pub(crate) fn try_lock_for(&self, timeout: Duration) -> TryLockResult<ValueMutexGuard<'_, T>> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			match self.inner.try_lock() {
+				Err(TryLockError::WouldBlock) => {
+					let now = Instant::now();
+					if deadline <= now {
+						break Err(TryLockError::WouldBlock);
+					}
+					std::thread::sleep((deadline - now).min(Duration::from_millis(1)));
+				}
+				result => break result,
+			}
+		}
+	}
+
+	#[cfg(feature = "parking_lot")]
+	pub(crate) fn try_lock_for(&self, timeout: Duration) -> TryLockResult<ValueMutexGuard<'_, T>> {
+		self.inner.try_lock_for(timeout).ok_or(TryLockError::WouldBlock)
+	}
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+use super::*;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+/// Tracks visited nodes during traversals.
+///
+/// By default, traversals use [`HashSet`] keyed by the identity of nodes. If nodes can be identified by dense integer ids stored in the values, implementing this for a bitset indexed by the ids may be faster and use less memory. This is used by methods like [`Internode::dfs_outgoing_with`].
+pub trait VisitedSet<T: Neighbors> {
+	/// Marks the node as visited, and returns `true` if it's not visited yet.
+	///
+	/// This is called while no node is locked, so the implementation may lock the node to extract its id.
+	fn insert(&mut self, node: &Internode<T>) -> bool;
+}
+
+impl<T: Neighbors, S: BuildHasher> VisitedSet<T> for HashSet<Internode<T>, S> {
+	fn insert(&mut self, node: &Internode<T>) -> bool { HashSet::insert(self, node.clone()) }
+}
\ No newline at end of file
@@ -1,6 +1,13 @@
+// Nodes are passed as `&*node` throughout, to make the `Node`-to-`Internode` deref explicit.
+#![allow(clippy::explicit_auto_deref)]
+
 use internode::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Debug;
+use std::fmt::Display;
 
+#[derive(Clone)]
 struct Entity {
 	value: &'static str,
 	succs: Vec<Internode<Entity>>,
@@ -24,6 +31,24 @@ impl Neighbors for Entity {
 	fn incoming(&self) -> Self::Iter<'_> { self.preds.iter().cloned() }
 }
 
+impl NeighborsMut for Entity {
+	fn outgoing_mut(&mut self) -> &mut Vec<Internode<Self>> { &mut self.succs }
+	fn incoming_mut(&mut self) -> &mut Vec<Internode<Self>> { &mut self.preds }
+}
+
+impl NeighborsSlice for Entity {
+	fn outgoing_slice(&self) -> &[Internode<Self>] { &self.succs }
+	fn incoming_slice(&self) -> &[Internode<Self>] { &self.preds }
+}
+
+impl std::hash::Hash for Entity {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.value.hash(state) }
+}
+
+impl PartialEq for Entity {
+	fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
 impl Debug for Entity {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let depth = f.precision().unwrap_or(0);
@@ -51,6 +76,13 @@ impl Debug for Entity {
 	}
 }
 
+impl Display for Entity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.value)?;
+		Ok(())
+	}
+}
+
 #[test]
 fn lifecycle_0() {
 	let (a_weak, b) = {
@@ -127,4 +159,1738 @@ fn traversal() {
 	assert!(a.dfs_incoming().eq([&*a, &*d, &*b, &*c].into_iter().cloned()));
 	assert!(a.bfs_outgoing().eq([&*a, &*b, &*c, &*d].into_iter().cloned()));
 	assert!(a.bfs_incoming().eq([&*a, &*d, &*b, &*c].into_iter().cloned()));
+}
+
+#[test]
+fn traversal_postorder() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	assert!(a.dfs_outgoing_postorder().eq([&*d, &*b, &*c, &*a].into_iter().cloned()));
+	assert!(a.dfs_incoming_postorder().eq([&*b, &*c, &*d, &*a].into_iter().cloned()));
+	assert!(b.dfs_outgoing_postorder().eq([&*c, &*a, &*d, &*b].into_iter().cloned()));
+}
+
+#[test]
+fn outgoing_rev() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	assert_eq!(a.outgoing_rev(), Some(vec![c.downgrade(), b.downgrade()]));
+	assert_eq!(b.outgoing_rev(), Some(vec![]));
+	let a_weak = a.downgrade();
+	drop((a, b, c));
+	assert_eq!(a_weak.outgoing_rev(), None);
+}
+
+#[test]
+fn dump() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	assert_eq!(b.dump(), "0: b -> [1]\n1: c -> [2]\n2: a -> [0]");
+}
+
+#[test]
+fn component_handle() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	let a_weak = a.downgrade();
+	let a_handle = a.clone().into_handle();
+	assert_eq!(a_handle.upgrade(), Some(a.clone()));
+	drop(a);
+	assert!(a_handle.upgrade().is_none());
+	assert!(a_weak.upgrade().is_some());
+	let a = a_weak.upgrade().unwrap();
+	assert_eq!(a_handle.upgrade(), Some(a.clone()));
+	drop(a);
+	assert!(a_handle.upgrade().is_none());
+	let b_handle = b.into_handle();
+	assert!(b_handle.upgrade().is_none());
+	assert!(a_handle.upgrade().is_none());
+	assert!(a_weak.upgrade().is_none());
+}
+
+#[test]
+fn degree_histogram_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*d);
+	Entity::add_edge(&*b, &*d);
+	assert_eq!(a.degree_histogram_outgoing(), [(0, 2), (1, 1), (3, 1)].into());
+	assert_eq!(d.degree_histogram_outgoing(), [(0, 1)].into());
+}
+
+#[test]
+fn prune() {
+	let a = Node::new(Entity::new("a"));
+	let (b_weak, c_weak, d) = {
+		let b = Node::new(Entity::new("b"));
+		let c = Node::new(Entity::new("c"));
+		let d = Node::new(Entity::new("d"));
+		Entity::add_edge(&*a, &*b);
+		Entity::add_edge(&*c, &*a);
+		Entity::add_edge(&*d, &*b);
+		(b.downgrade(), c.downgrade(), d)
+	};
+	internode::prune(std::slice::from_ref(&a));
+	assert!(b_weak.upgrade().is_some());
+	assert!(c_weak.upgrade().is_none());
+	assert!(d.lock().succs.is_empty());
+	assert!(b_weak.lock().unwrap().preds.iter().eq([&*a]));
+	assert_eq!(a.lock().preds.len(), 0);
+}
+
+#[test]
+fn map_reduce_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	assert_eq!(b.map_reduce_outgoing(String::new(), |acc, entity| acc + entity.value), "bca");
+}
+
+#[test]
+fn subgraph_filtered_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	let subgraph = a.subgraph_filtered_outgoing(|entity| entity.value != "b");
+	assert_eq!(subgraph.len(), 3);
+	assert_eq!(subgraph.edge_count(), 3);
+	assert!(subgraph.index_of(&b).is_none());
+	let [a, c, d] = [&a, &c, &d].map(|node| subgraph.index_of(node).unwrap());
+	assert_eq!(subgraph.outgoing(a), [c]);
+	assert_eq!(subgraph.outgoing(c), [d]);
+	assert_eq!(subgraph.incoming(a), [d]);
+}
+
+#[test]
+fn traversal_concurrent() {
+	let nodes = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*nodes[0], &*nodes[1]);
+	Entity::add_edge(&*nodes[1], &*nodes[2]);
+	Entity::add_edge(&*nodes[2], &*nodes[3]);
+	std::thread::scope(|scope| {
+		scope.spawn(|| {
+			for i in 0..1000 {
+				let (from, to) = (&nodes[i % 4], &nodes[(i + 2) % 4]);
+				Entity::add_edge(from, to);
+				let mut guard = from.lock();
+				guard.succs.shrink_to_fit();
+				guard.succs.pop();
+				drop(guard);
+				to.lock().preds.pop();
+			}
+		});
+		for _ in 0..1000 {
+			assert!(nodes[0].dfs_outgoing().count() <= 4);
+			assert!(nodes[3].bfs_incoming().count() <= 4);
+		}
+	});
+}
+
+#[test]
+fn k_core_undirected() {
+	let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*e);
+	let mut core = e.k_core_undirected(2);
+	core.sort_by_key(|node| node.lock().unwrap().value);
+	assert_eq!(core, [&*a, &*b, &*c].map(Clone::clone));
+	assert_eq!(e.k_core_undirected(1).len(), 5);
+	assert!(e.k_core_undirected(3).is_empty());
+}
+
+#[test]
+fn map_counts_only_writes() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	let mut cached = CachedInternode::new((*a).clone());
+	let snapshot = cached.outgoing().unwrap().as_ptr();
+	let value = a.lock().map(|entity| &mut entity.value);
+	assert_eq!(*value, "a");
+	drop(value);
+	assert_eq!(cached.outgoing().unwrap().as_ptr(), snapshot);
+	a.lock().map(|entity| &mut entity.succs).clear();
+	assert!(cached.outgoing().unwrap().is_empty());
+}
+
+#[test]
+fn lock_timeout_ms() {
+	let a = Node::new(Entity::new("a"));
+	let guard = a.lock();
+	assert_eq!(a.lock_timeout_ms(10).err(), Some(LockStatus::TimedOut));
+	drop(guard);
+	assert!(a.lock_timeout_ms(10).is_ok());
+	let a_weak = a.downgrade();
+	drop(a);
+	assert_eq!(a_weak.lock_timeout_ms(10).err(), Some(LockStatus::Dead));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_outgoing() {
+	use rayon::iter::ParallelIterator;
+	let a = Node::new(Entity::new("a"));
+	let nodes = (0..100).map(|_| Node::new(Entity::new("b"))).collect::<Vec<_>>();
+	for node in &nodes {
+		Entity::add_edge(&*a, node);
+	}
+	assert_eq!(a.par_outgoing().filter(|node| node.lock().unwrap().value == "b").count(), 100);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn bfs_outgoing_async() {
+	let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	let guard = c.lock();
+	let nodes = runtime.block_on(async {
+		assert!(a.outgoing_snapshot_async().await.unwrap().iter().eq([&*b]));
+		// The search waits for `c` without blocking the only worker thread, so this task can go on to release it.
+		let pending = tokio::task::spawn(async move { (*a).bfs_outgoing_async().await });
+		for _ in 0..50 {
+			tokio::task::yield_now().await;
+			std::thread::sleep(std::time::Duration::from_millis(1));
+		}
+		assert!(!pending.is_finished());
+		drop(guard);
+		pending.await.unwrap()
+	});
+	assert_eq!(nodes.iter().map(|node| node.lock().unwrap().value).collect::<Vec<_>>(), [
+		"a", "b", "c"
+	]);
+}
+
+#[test]
+fn is_dag_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	assert!(a.is_dag_outgoing());
+	Entity::add_edge(&*d, &*d);
+	assert!(!a.is_dag_outgoing());
+	assert!(!d.is_dag_outgoing());
+}
+
+#[test]
+fn count_outgoing_matching() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*a);
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	let count = a.count_outgoing_matching(|node| node.lock().unwrap().value != "b");
+	assert_eq!(count, Some(2));
+}
+
+#[test]
+fn fold_edges_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	let edges = a.fold_edges_outgoing(Vec::new(), |mut acc, from, to| {
+		acc.push(format!("{from}{to}"));
+		acc
+	});
+	assert_eq!(edges, ["ab", "bc", "ca"]);
+}
+
+#[test]
+fn new_with() {
+	let mut registry = Vec::new();
+	let a = Node::new_with(|node| {
+		registry.push(node.clone());
+		assert!(node.lock_timeout_ms(0).is_err());
+		let mut entity = Entity::new("a");
+		entity.succs.push(node.downgrade());
+		entity.preds.push(node.downgrade());
+		entity
+	});
+	assert!(registry == [a.clone()]);
+	assert!(a.outgoing().eq([a.downgrade()]));
+	let a_weak = a.downgrade();
+	drop((a, registry));
+	assert!(a_weak.upgrade().is_none());
+}
+
+#[test]
+fn gc_from_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c_weak = {
+		let c = Node::new(Entity::new("c"));
+		Entity::add_edge(&*a, &*b);
+		Entity::add_edge(&*b, &*a);
+		Entity::add_edge(&*c, &*a);
+		c.downgrade()
+	};
+	assert!(c_weak.upgrade().is_some());
+	b.gc_from_outgoing();
+	assert!(c_weak.upgrade().is_none());
+	assert!(a.incoming().eq([b.downgrade()]));
+}
+
+#[test]
+fn connect() {
+	let a = Node::new(Entity::new("a"));
+	let b_weak = {
+		let b = Node::new(Entity::new("b"));
+		assert!(a.connect(&b));
+		assert!(b.connect(&b));
+		assert!(b.incoming().eq([a.downgrade(), b.downgrade()]));
+		b.downgrade()
+	};
+	assert!(b_weak.upgrade().is_some());
+	assert!(!b_weak.disconnect(&a));
+	assert!(a.disconnect(&b_weak));
+	assert!(a.outgoing().next().is_none());
+	assert!(b_weak.upgrade().is_none());
+	assert!(!a.connect(&b_weak));
+}
+
+struct Indexed(Vec<Internode<Indexed>>);
+
+impl Neighbors for Indexed {
+	type Iter<'a> = std::iter::Cloned<std::slice::Iter<'a, Internode<Indexed>>>;
+	fn outgoing(&self) -> Self::Iter<'_> { self.0.iter().cloned() }
+	fn incoming(&self) -> Self::Iter<'_> { self.0[..0].iter().cloned() }
+}
+
+impl IndexedNeighbors for Indexed {
+	fn outgoing_mut(&mut self) -> &mut Vec<Internode<Self>> { &mut self.0 }
+}
+
+#[test]
+fn connect_indexed() {
+	let a = Node::new(Indexed(Vec::new()));
+	let b_weak = {
+		let b = Node::new(Indexed(Vec::new()));
+		assert!(a.connect_indexed(&b));
+		assert!(b.connect_indexed(&b));
+		assert!(b.incoming().eq([a.downgrade(), b.downgrade()]));
+		assert!(b.dfs_incoming().eq([b.downgrade(), a.downgrade()]));
+		b.downgrade()
+	};
+	assert!(b_weak.upgrade().is_some());
+	assert!(!b_weak.disconnect_indexed(&a));
+	assert!(a.disconnect_indexed(&b_weak));
+	assert!(b_weak.upgrade().is_none());
+	assert!(!a.connect_indexed(&b_weak));
+	let c_weak = {
+		let c = Node::new(Indexed(Vec::new()));
+		assert!(a.connect_indexed(&c));
+		c.downgrade()
+	};
+	let a_weak = a.downgrade();
+	drop(a);
+	assert!(a_weak.upgrade().is_none());
+	assert!(c_weak.upgrade().is_none());
+	assert!(c_weak.incoming().next().is_none());
+}
+
+#[test]
+fn longest_path_depth_outgoing() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*d);
+	let depths = a.longest_path_depth_outgoing().unwrap();
+	assert_eq!([&a, &b, &c, &d].map(|node| depths[node]), [0, 1, 2, 3]);
+	Entity::add_edge(&*d, &*b);
+	let error = a.longest_path_depth_outgoing().unwrap_err();
+	assert!([&b, &c, &d].iter().any(|node| error.node() == &***node));
+}
+
+#[test]
+fn try_value() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	let value = a.try_value().unwrap();
+	assert_eq!(value.value, "a");
+	assert!(value.succs == [b.downgrade()]);
+	let a_weak = a.downgrade();
+	drop((a, b));
+	assert!(a_weak.try_value().is_none());
+	drop(value);
+}
+
+#[test]
+fn outgoing_nodes() {
+	let a = Node::new(Entity::new("a"));
+	let (b_weak, c_weak) = {
+		let b = Node::new(Entity::new("b"));
+		let c = Node::new(Entity::new("c"));
+		Entity::add_edge(&*a, &*b);
+		Entity::add_edge(&*b, &*c);
+		Entity::add_edge(&*a, &*c);
+		(b.downgrade(), c.downgrade())
+	};
+	let mut nodes = a.outgoing_nodes();
+	let b = nodes.next().unwrap();
+	assert_eq!(*b, b_weak);
+	drop(a);
+	let c = nodes.next().unwrap();
+	assert_eq!(*c, c_weak);
+	assert!(nodes.next().is_none());
+	drop((b, c));
+	assert!(c_weak.upgrade().is_none());
+}
+
+#[test]
+fn to_edge_list_csv() {
+	let [a, b, c] = ["a", "b", "c,"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	let csv = a.to_edge_list_csv(|entity| entity.value.to_string());
+	assert_eq!(csv, "from,to\na,b\nb,\"c,\"\n\"c,\",a\n");
+}
+
+#[test]
+fn collect_outgoing() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*c, &*a);
+	let (nodes, adjacency) = a.collect_outgoing();
+	assert_eq!(nodes, [&a, &b, &c].map(|node| node.downgrade()));
+	assert_eq!(adjacency[&*a], [b.downgrade(), c.downgrade()]);
+	assert_eq!(adjacency[&*b], []);
+	assert_eq!(adjacency[&*c], [a.downgrade()]);
+}
+
+#[test]
+fn lifecycle_3() {
+	let (a_weak, b_weak, c) = {
+		let a = Node::new(Entity::new("a"));
+		let b = Node::new(Entity::new("b"));
+		let c = Node::new(Entity::new("c"));
+		Entity::add_edge(&*a, &*b);
+		Entity::add_edge(&*c, &*b);
+		(a.downgrade(), b.downgrade(), c)
+	};
+	assert!(a_weak.upgrade().is_some());
+	assert!(b_weak.upgrade().is_some());
+	assert!(c.lock().succs == [b_weak.clone()]);
+	drop(c);
+	assert!(a_weak.upgrade().is_none());
+	assert!(b_weak.upgrade().is_none());
+}
+
+#[test]
+fn diameter_estimate_outgoing() {
+	let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*c, &*d);
+	assert_eq!(a.diameter_estimate_outgoing(), 2);
+	Entity::add_edge(&*d, &*e);
+	Entity::add_edge(&*e, &*a);
+	assert_eq!(a.diameter_estimate_outgoing(), 3);
+	assert_eq!(b.diameter_estimate_outgoing(), 0);
+}
+
+#[test]
+fn with_both() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let concat = |x: &Entity, y: &Entity| format!("{}{}", x.value, y.value);
+	assert_eq!(a.with_both(&b, concat).as_deref(), Some("ab"));
+	assert_eq!(b.with_both(&a, concat).as_deref(), Some("ba"));
+	assert_eq!(a.with_both(&a, concat).as_deref(), Some("aa"));
+	let b_weak = b.downgrade();
+	drop(b);
+	assert_eq!(a.with_both(&b_weak, concat), None);
+}
+
+#[test]
+fn follow_outgoing() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	let any = || Box::new(|_: &Entity| true) as Box<dyn Fn(&Entity) -> bool>;
+	let not_b = Box::new(|entity: &Entity| entity.value != "b");
+	assert_eq!(a.follow_outgoing(&[]), [a.downgrade()]);
+	assert_eq!(a.follow_outgoing(&[any(), any()]), [d.downgrade()]);
+	assert_eq!(a.follow_outgoing(&[not_b, any(), any()]), [a.downgrade()]);
+	assert_eq!(a.follow_outgoing(&[any(), Box::new(|entity| entity.value == "a")]), []);
+}
+
+#[test]
+fn outgoing_len_hint() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	assert_eq!(a.outgoing_len_hint(), (2, Some(2)));
+	let c_weak = c.downgrade();
+	drop((a, b, c));
+	assert_eq!(c_weak.outgoing_len_hint(), (0, Some(0)));
+}
+
+#[test]
+fn traversal_with() {
+	struct Bitset(u32);
+	impl VisitedSet<Entity> for Bitset {
+		fn insert(&mut self, node: &Internode<Entity>) -> bool {
+			let bit = 1 << (node.lock().unwrap().value.as_bytes()[0] - b'a');
+			let inserted = self.0 & bit == 0;
+			self.0 |= bit;
+			inserted
+		}
+	}
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	assert!(a.dfs_outgoing_with(Bitset(0)).eq(a.dfs_outgoing()));
+	assert!(a.dfs_incoming_with(Bitset(0)).eq(a.dfs_incoming()));
+	assert!(a.bfs_outgoing_with(Bitset(0)).eq(a.bfs_outgoing()));
+	assert!(a.bfs_incoming_with(Bitset(0)).eq(a.bfs_incoming()));
+	assert_eq!(a.bfs_outgoing_with(Bitset(0b10)).count(), 3);
+}
+
+#[test]
+fn eulerian_path_outgoing() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	Entity::add_edge(&*a, &*d);
+	Entity::add_edge(&*d, &*a);
+	let path = b.eulerian_path_outgoing().unwrap();
+	assert_eq!(path, [&b, &c, &a, &d, &a, &b].map(|node| node.downgrade()));
+	Entity::add_edge(&*b, &*d);
+	let path = c.eulerian_path_outgoing().unwrap();
+	assert_eq!(path, [&b, &c, &a, &b, &d, &a, &d].map(|node| node.downgrade()));
+	Entity::add_edge(&*c, &*d);
+	assert!(c.eulerian_path_outgoing().is_none());
+}
+
+#[test]
+fn symmetric() {
+	#[derive(Default)]
+	struct Vertex {
+		edges: Symmetric<Vertex>,
+	}
+	impl SymmetricNeighbors for Vertex {
+		fn symmetric(&self) -> &Symmetric<Self> { &self.edges }
+		fn symmetric_mut(&mut self) -> &mut Symmetric<Self> { &mut self.edges }
+	}
+	let a = Node::new(Vertex::default());
+	let b_weak = {
+		let b = Node::new(Vertex::default());
+		a.connect(&b);
+		b.downgrade()
+	};
+	assert!(b_weak.incoming().eq([a.downgrade()]));
+	assert!(a.lock().edges.outgoing() == [b_weak.clone()]);
+	a.disconnect(&b_weak);
+	assert!(b_weak.upgrade().is_none());
+}
+
+#[test]
+fn outgoing_unique() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*b);
+	assert_eq!(a.outgoing().count(), 3);
+	assert!(a.outgoing_unique().eq([b.downgrade(), c.downgrade()]));
+}
+
+#[test]
+fn ancestors_until() {
+	let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*e);
+	Entity::add_edge(&*d, &*e);
+	let boundary = HashSet::from([b.downgrade()]);
+	assert_eq!(e.ancestors_until(&boundary), [&c, &d, &b].map(|node| node.downgrade()));
+	assert_eq!(e.ancestors_until(&HashSet::new()).len(), 4);
+}
+
+#[test]
+fn to_adjacency_matrix_outgoing() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*a);
+	Entity::add_edge(&*c, &*c);
+	let (nodes, matrix) = a.to_adjacency_matrix_outgoing();
+	assert_eq!(nodes, [&a, &b, &c].map(|node| node.downgrade()));
+	assert_eq!(matrix, [[false, true, false], [false, false, true], [true, false, true]]);
+}
+
+#[test]
+fn mapped_guard() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let mut succs = a.lock().map(|entity| &mut entity.succs);
+	succs.push(b.downgrade());
+	assert!(a.lock_timeout_ms(0).is_err());
+	drop(succs);
+	b.lock().preds.push(a.downgrade());
+	assert!(a.outgoing().eq([b.downgrade()]));
+	assert_eq!(format!("{}", a.lock().map(|entity| &mut entity.value)), "a");
+}
+
+#[test]
+fn component_roots() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*c, &*b);
+	Entity::add_edge(&*b, &*d);
+	assert_eq!(d.component_roots(), [a.downgrade(), c.downgrade()]);
+	Entity::add_edge(&*d, &*a);
+	Entity::add_edge(&*d, &*c);
+	assert_eq!(d.component_roots(), []);
+}
+
+#[test]
+fn component_size() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*c, &*b);
+	assert_eq!(a.component_size(), 3);
+	assert_eq!(d.component_size(), 1);
+	Entity::add_edge(&*b, &*a);
+	Entity::add_edge(&*d, &*c);
+	assert_eq!(b.component_size(), 4);
+}
+
+#[test]
+fn drain_component_outgoing() {
+	let (a, b_weak, c_weak) = {
+		let a = Node::new(Entity::new("a"));
+		let b = Node::new(Entity::new("b"));
+		let c = Node::new(Entity::new("c"));
+		Entity::add_edge(&*a, &*b);
+		Entity::add_edge(&*b, &*a);
+		Entity::add_edge(&*c, &*a);
+		(a, b.downgrade(), c.downgrade())
+	};
+	let b = b_weak.upgrade().unwrap();
+	let a = a.drain_component_outgoing().unwrap_err();
+	drop(b);
+	let values = a.drain_component_outgoing().unwrap();
+	assert_eq!(values.iter().map(|entity| entity.value).collect::<Vec<_>>(), ["a", "b"]);
+	assert!(b_weak.upgrade().is_none());
+	assert!(c_weak.upgrade().is_none());
+	assert!(values[1].succs[0].upgrade().is_none());
+}
+
+#[test]
+fn group_outgoing_by() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&*a, &*a);
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*d);
+	Entity::add_edge(&*d, &*a);
+	let groups = a.group_outgoing_by(|entity| entity.succs.len());
+	assert_eq!(groups[&4], [a.downgrade()]);
+	assert_eq!(groups[&0], [b.downgrade(), c.downgrade()]);
+	assert_eq!(groups[&1], [d.downgrade()]);
+}
+
+#[test]
+fn traversal_fused() {
+	fn assert_fused(mut iter: impl std::iter::FusedIterator) {
+		while iter.next().is_some() {}
+		assert!(iter.next().is_none());
+		assert!(iter.next().is_none());
+	}
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*a);
+	assert_fused(a.dfs_outgoing());
+	assert_fused(a.dfs_incoming());
+	assert_fused(a.bfs_outgoing());
+	assert_fused(a.bfs_incoming());
+	assert_fused(a.dfs_outgoing_with(HashSet::new()));
+	assert_fused(a.outgoing_nodes());
+	assert_fused(a.outgoing_unique());
+}
+
+#[test]
+fn bfs_outgoing_from_many() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	let e = Node::new(Entity::new("e"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*e, &*d);
+	Entity::add_edge(&*d, &*c);
+	Entity::add_edge(&*c, &*a);
+	let result =
+		internode::bfs_outgoing_from_many([a.downgrade(), e.downgrade()]).collect::<Vec<_>>();
+	assert!(
+		result
+			== [
+				(a.downgrade(), 0, a.downgrade()),
+				(e.downgrade(), 0, e.downgrade()),
+				(b.downgrade(), 1, a.downgrade()),
+				(d.downgrade(), 1, e.downgrade()),
+				(c.downgrade(), 2, a.downgrade()),
+			]
+	);
+}
+
+#[test]
+fn debug_assert_acyclic_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	a.debug_assert_acyclic_outgoing();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "found a cycle at Internode(Entity(")]
+fn debug_assert_acyclic_outgoing_cycle() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*a);
+	a.debug_assert_acyclic_outgoing();
+}
+
+#[test]
+fn outgoing_take() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	assert!(a.outgoing_take(1) == [b.downgrade()]);
+	assert!(a.outgoing_take(5) == [b.downgrade(), c.downgrade()]);
+	assert!(a.outgoing_take(0).is_empty());
+	let d = Node::new(Entity::new("d")).downgrade();
+	assert!(d.outgoing_take(1).is_empty());
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct Atom(&'static str);
+
+impl Neighbors for Atom {
+	type Iter<'a> = std::iter::Empty<Internode<Atom>>;
+	fn outgoing(&self) -> Self::Iter<'_> { std::iter::empty() }
+	fn incoming(&self) -> Self::Iter<'_> { std::iter::empty() }
+}
+
+#[test]
+fn interner() {
+	let mut interner = Interner::new();
+	let a = interner.intern(Atom("a"));
+	let b = interner.intern(Atom("b"));
+	assert!(interner.intern(Atom("a")) == a);
+	assert!(a != b);
+	assert_eq!(interner.len(), 2);
+	let b_weak = b.downgrade();
+	drop(b);
+	assert!(b_weak.upgrade().is_none());
+	let b = interner.intern(Atom("b"));
+	assert!(b.downgrade() != b_weak);
+	drop(b);
+	interner.purge();
+	assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn spanning_tree_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	let (a, b, c, d) = (a.downgrade(), b.downgrade(), c.downgrade(), d.downgrade());
+	assert!(a.spanning_tree_outgoing().eq([
+		(a.clone(), b.clone()),
+		(b.clone(), d.clone()),
+		(a.clone(), c.clone()),
+	]));
+}
+
+#[test]
+fn replace_value() {
+	let a = Node::new(Entity::new("a"));
+	let old = a.replace_value(Entity::new("b"));
+	assert_eq!(old.value, "a");
+	assert_eq!(a.lock().value, "b");
+}
+
+#[test]
+fn count_paths_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*a, &*d);
+	assert_eq!(a.count_paths_outgoing(&d).unwrap(), 3);
+	assert_eq!(a.count_paths_outgoing(&a).unwrap(), 1);
+	assert_eq!(d.count_paths_outgoing(&a).unwrap(), 0);
+	Entity::add_edge(&*d, &*b);
+	Entity::add_edge(&*b, &*d);
+	assert!(a.count_paths_outgoing(&d).is_err());
+}
+
+#[test]
+fn lock_component_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*a);
+	Entity::add_edge(&*c, &*a);
+	let mut nodes = vec![c.downgrade()];
+	let mut guards = b.lock_component_outgoing(&mut nodes);
+	assert_eq!(guards.len(), 2);
+	assert!(guards.iter().all(|guard| guard.value != "c"));
+	for guard in &mut guards {
+		guard.value = "x";
+	}
+	drop(guards);
+	assert!(nodes.len() == 2 && nodes.contains(&a) && nodes.contains(&b));
+	assert_eq!(a.lock().value, "x");
+	assert_eq!(b.lock().value, "x");
+	assert_eq!(c.lock().value, "c");
+}
+
+#[test]
+fn dfs_outgoing_owned() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	let (a_weak, b_weak, c_weak) = (a.downgrade(), b.downgrade(), c.downgrade());
+	drop((b, c));
+	let mut iter = a.dfs_outgoing_owned();
+	assert!(iter.next() == Some(a_weak.clone()));
+	assert!(iter.next() == Some(b_weak));
+	assert!(a_weak.upgrade().is_some());
+	assert!(iter.next() == Some(c_weak));
+	assert!(iter.next().is_none());
+	assert!(a_weak.upgrade().is_none());
+}
+
+#[test]
+fn communities_undirected() {
+	let nodes = ["a", "b", "c", "d", "e", "f"].map(|value| Node::new(Entity::new(value)));
+	for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+		Entity::add_edge(&nodes[from], &nodes[to]);
+	}
+	let communities = nodes[0].communities_undirected(0);
+	assert_eq!(communities.len(), 2);
+	for community in &communities {
+		let values =
+			community.iter().map(|node| node.lock().unwrap().value).collect::<HashSet<_>>();
+		assert!(
+			values == HashSet::from(["a", "b", "c"]) || values == HashSet::from(["d", "e", "f"])
+		);
+	}
+	assert!(communities == nodes[0].communities_undirected(0));
+}
+
+#[test]
+fn at_distance_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	assert!(a.at_distance_outgoing(0) == [a.downgrade()]);
+	assert!(a.at_distance_outgoing(1) == [b.downgrade(), c.downgrade()]);
+	assert!(a.at_distance_outgoing(2) == [d.downgrade()]);
+	assert!(a.at_distance_outgoing(3).is_empty());
+}
+
+#[test]
+fn topo_layers_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*a, &*d);
+	let layers = a.topo_layers_outgoing().unwrap();
+	assert!(
+		layers == [vec![a.downgrade()], vec![b.downgrade(), c.downgrade()], vec![d.downgrade()]]
+	);
+	Entity::add_edge(&*d, &*a);
+	assert!(a.topo_layers_outgoing().is_err());
+}
+
+#[test]
+fn replace_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	a.connect(&b);
+	let b = {
+		let b_weak = b.downgrade();
+		drop(b);
+		b_weak
+	};
+	let previous = a.replace_outgoing(vec![c.downgrade()]).unwrap();
+	assert!(previous == [b.clone()]);
+	assert!(a.outgoing_snapshot().unwrap() == [c.downgrade()]);
+	assert!(b.upgrade().is_some());
+	a.replace_outgoing(vec![b.clone()]);
+	b.lock().unwrap().preds.clear();
+	a.replace_outgoing(Vec::new());
+	assert!(b.upgrade().is_none());
+	let d = Node::new(Entity::new("d")).downgrade();
+	assert!(d.replace_outgoing(Vec::new()).is_none());
+}
+
+#[test]
+fn reachable_set() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*b);
+	Entity::add_edge(&*d, &*c);
+	assert!(b.reachable_set_outgoing() == HashSet::from([b.downgrade(), c.downgrade()]));
+	assert!(b.reachable_set_outgoing() == b.dfs_outgoing().collect());
+	assert!(b.reachable_set_incoming() == b.dfs_incoming().collect());
+	assert_eq!(b.reachable_set_incoming().len(), 4);
+}
+
+#[test]
+fn lock_tracked() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	let (b, c) = {
+		let weak = (b.downgrade(), c.downgrade());
+		drop((b, c));
+		weak
+	};
+	b.lock().unwrap().preds.clear();
+	c.lock().unwrap().preds.clear();
+	a.lock().succs.retain(|node| node != &b);
+	assert!(b.upgrade().is_some());
+	let mut guard = a.lock_tracked().unwrap();
+	guard.succs.retain(|node| node != &c);
+	drop(guard);
+	assert!(c.upgrade().is_none());
+	assert!(a.downgrade().upgrade().is_some());
+}
+
+#[test]
+fn bfs_outgoing_pruned() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	assert!(a
+		.bfs_outgoing_pruned(|value| value.value != "b")
+		.eq([&*a, &*b, &*c, &*d].into_iter().cloned()));
+	assert!(a
+		.bfs_outgoing_pruned(|value| value.value == "a")
+		.eq([&*a, &*b, &*c].into_iter().cloned()));
+	assert!(a.bfs_outgoing_pruned(|_| false).eq([a.downgrade()]));
+}
+
+#[test]
+fn structural_hash_outgoing() {
+	use std::collections::hash_map::DefaultHasher;
+	use std::hash::Hasher;
+	fn build() -> [Node<Entity>; 3] {
+		let nodes = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+		Entity::add_edge(&nodes[0], &nodes[1]);
+		Entity::add_edge(&nodes[1], &nodes[2]);
+		Entity::add_edge(&nodes[2], &nodes[0]);
+		nodes
+	}
+	fn hash(node: &Internode<Entity>) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		node.structural_hash_outgoing(&mut hasher);
+		hasher.finish()
+	}
+	let x = build();
+	let y = build();
+	assert_eq!(hash(&x[0]), hash(&y[0]));
+	assert_ne!(hash(&x[0]), hash(&x[1]));
+	Entity::add_edge(&y[0], &y[2]);
+	assert_ne!(hash(&x[0]), hash(&y[0]));
+}
+
+#[test]
+fn both_directions() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	let (outgoing, incoming) = b.both_directions().unwrap();
+	assert!(outgoing == [c.downgrade()]);
+	assert!(incoming == [a.downgrade()]);
+	let d = Node::new(Entity::new("d")).downgrade();
+	assert!(d.both_directions().is_none());
+}
+
+#[test]
+fn bfs_incoming_tree() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*d, &*a);
+	let parents = d.bfs_incoming_tree();
+	assert_eq!(parents.len(), 3);
+	assert!(!parents.contains_key(&d));
+	assert!(parents[&b] == d.downgrade());
+	assert!(parents[&c] == d.downgrade());
+	assert!(parents[&a] == b.downgrade());
+}
+
+#[cfg(feature = "derive")]
+#[derive(Neighbors)]
+struct Derived {
+	#[outgoing]
+	succs: Vec<Internode<Derived>>,
+	#[incoming]
+	preds: Vec<Internode<Derived>>,
+}
+
+#[cfg(feature = "derive")]
+#[derive(Neighbors)]
+struct DerivedOutgoingOnly<'a>(&'a str, #[outgoing] Vec<Internode<Self>>);
+
+#[test]
+#[cfg(feature = "derive")]
+fn derive_neighbors() {
+	let a = Node::new(Derived { succs: Vec::new(), preds: Vec::new() });
+	let b = Node::new(Derived { succs: Vec::new(), preds: Vec::new() });
+	a.lock().succs.push(b.downgrade());
+	b.lock().preds.push(a.downgrade());
+	assert!(a.outgoing_snapshot().unwrap() == [b.downgrade()]);
+	assert!(b.incoming_snapshot().unwrap() == [a.downgrade()]);
+	let c = Node::new(DerivedOutgoingOnly("c", Vec::new()));
+	let d = Node::new(DerivedOutgoingOnly("d", vec![c.downgrade()]));
+	assert!(d.outgoing_snapshot().unwrap() == [c.downgrade()]);
+	assert!(d.incoming_snapshot().unwrap().is_empty());
+	assert_eq!(d.lock().0, "d");
+}
+
+#[test]
+fn index_outgoing_by() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let x = Node::new(Entity::new("x"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*x, &*a);
+	let index = a.index_outgoing_by(|value| value.value);
+	assert_eq!(index.len(), 3);
+	assert!(
+		index["a"] == a.downgrade() && index["b"] == b.downgrade() && index["c"] == c.downgrade()
+	);
+	let index = a.index_outgoing_by(|value| value.value == "a");
+	assert!(index[&true] == a.downgrade() && index[&false] == c.downgrade());
+}
+
+#[test]
+#[cfg(feature = "rand")]
+fn sample_outgoing() {
+	use rand::rngs::StdRng;
+	use rand::SeedableRng;
+	let a = Node::new(Entity::new("a"));
+	let nodes = ["b", "c", "d", "e", "f"].map(|value| Node::new(Entity::new(value)));
+	for node in &nodes {
+		Entity::add_edge(&a, node);
+	}
+	let sample = a.sample_outgoing(2, StdRng::seed_from_u64(0));
+	assert_eq!(sample.len(), 2);
+	assert!(sample[0] != sample[1]);
+	assert!(sample.iter().all(|node| nodes.iter().any(|n| &**n == node)));
+	assert!(sample == a.sample_outgoing(2, StdRng::seed_from_u64(0)));
+	assert!(a.sample_outgoing(10, StdRng::seed_from_u64(0)) == a.outgoing_snapshot().unwrap());
+}
+
+#[test]
+fn absorb_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*a);
+	Entity::add_edge(&*b, &*a);
+	let values = std::cell::RefCell::new(Vec::new());
+	assert!(a.absorb_outgoing(|value, neighbor| {
+		values.borrow_mut().push((value.value, neighbor.value));
+	}));
+	assert!(values.into_inner() == [("a", "b"), ("a", "c")]);
+	let threads = [a.downgrade(), b.downgrade()].map(|node| {
+		std::thread::spawn(move || {
+			for _ in 0..100 {
+				node.absorb_outgoing(|_, _| {});
+			}
+		})
+	});
+	for thread in threads {
+		thread.join().unwrap();
+	}
+}
+
+#[test]
+fn cached_internode() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	let mut cached = CachedInternode::new(a.downgrade());
+	assert!(cached.outgoing().unwrap() == [b.downgrade()]);
+	assert!(cached.incoming().unwrap().is_empty());
+	let guard = a.lock();
+	// Doesn't lock `a` since nothing has changed.
+	assert!(cached.outgoing().unwrap() == [b.downgrade()]);
+	drop(guard);
+	a.connect(&c);
+	assert!(cached.outgoing().unwrap() == [b.downgrade(), c.downgrade()]);
+	Entity::add_edge(&*c, &*a);
+	assert!(cached.incoming().unwrap() == [c.downgrade()]);
+	let a_weak = a.downgrade();
+	drop((a, b, c));
+	assert!(a_weak.upgrade().is_none());
+	assert!(cached.outgoing().is_none());
+}
+
+#[test]
+fn bfs_outgoing_timeout() {
+	use std::time::Duration;
+	let nodes = (0..200).map(|_| Node::new(Entity::new("x"))).collect::<Vec<_>>();
+	for pair in nodes.windows(2) {
+		Entity::add_edge(&pair[0], &pair[1]);
+	}
+	let (visited, completed) = nodes[0].bfs_outgoing_timeout(Duration::from_secs(60));
+	assert!(completed);
+	assert!(visited.iter().eq(nodes[0].bfs_outgoing().collect::<Vec<_>>().iter()));
+	let (visited, completed) = nodes[0].bfs_outgoing_timeout(Duration::ZERO);
+	assert!(!completed);
+	assert!(visited.is_empty());
+}
+
+#[test]
+fn two_hop_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*b, &*d);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*c, &*a);
+	assert!(a.two_hop_outgoing().eq([c.downgrade(), d.downgrade()]));
+}
+
+#[test]
+fn is_tree_undirected() {
+	let a = Node::new(Entity::new("a"));
+	assert!(a.is_tree_undirected());
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*d, &*c);
+	assert!(b.is_tree_undirected());
+	Entity::add_edge(&*b, &*d);
+	assert!(!b.is_tree_undirected());
+	let e = Node::new(Entity::new("e"));
+	let f = Node::new(Entity::new("f"));
+	Entity::add_edge(&*e, &*f);
+	Entity::add_edge(&*f, &*e);
+	assert!(!e.is_tree_undirected());
+}
+
+#[test]
+fn raw() {
+	let a = Node::new(Entity::new("a"));
+	let ptr = a.as_raw();
+	assert_eq!(ptr, a.downgrade().as_raw());
+	let a_raw = unsafe { Internode::<Entity>::from_raw(ptr) };
+	assert!(a_raw == a.downgrade());
+	drop(a_raw);
+	assert_eq!(a.lock().value, "a");
+}
+
+#[test]
+fn partition_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*b);
+	let (matching, others) = a.partition_outgoing(|node| node == &*b).unwrap();
+	assert!(matching == [b.downgrade(), b.downgrade()]);
+	assert!(others == [c.downgrade()]);
+	let d = Node::new(Entity::new("d")).downgrade();
+	assert!(d.partition_outgoing(|_| true).is_none());
+}
+
+#[test]
+fn component_checksum_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	let checksum = a.component_checksum_outgoing();
+	a.lock().value = "x";
+	assert_eq!(a.component_checksum_outgoing(), checksum);
+	Entity::add_edge(&*a, &*b);
+	let checksum_parallel = a.component_checksum_outgoing();
+	assert_ne!(checksum_parallel, checksum);
+	Entity::add_edge(&*b, &*c);
+	assert_ne!(a.component_checksum_outgoing(), checksum_parallel);
+}
+
+#[test]
+fn try_connect() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	assert!(a.try_connect(&b).is_ok());
+	assert!(b.try_connect(&c).is_ok());
+	let error = c.try_connect(&a).unwrap_err();
+	assert!(matches!(error, ConnectError::WouldCycle { .. }));
+	assert!(error.from() == &*c && error.to() == &*a);
+	assert!(matches!(a.try_connect(&a), Err(ConnectError::WouldCycle { .. })));
+	assert!(c.outgoing_snapshot().unwrap().is_empty());
+	assert!(a.try_connect(&c).is_ok());
+	assert!(a.is_dag_outgoing());
+	let d = {
+		let d = Node::new(Entity::new("d"));
+		d.downgrade()
+	};
+	assert!(matches!(a.try_connect(&d), Err(ConnectError::Dead { .. })));
+	assert!(matches!(d.try_connect(&a), Err(ConnectError::Dead { .. })));
+	assert!(a.outgoing().eq([b.downgrade(), c.downgrade()]));
+}
+
+#[test]
+fn ord() {
+	use std::collections::BTreeSet;
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	assert!(a < b && b < c);
+	assert!(a.downgrade() < b.downgrade());
+	let set = BTreeSet::from([c.downgrade(), a.downgrade(), b.downgrade(), a.downgrade()]);
+	assert!(set.into_iter().eq([a.downgrade(), b.downgrade(), c.downgrade()]));
+}
+
+#[test]
+fn edge_multiplicity_to() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*b);
+	assert_eq!(a.edge_multiplicity_to(&b), Some(2));
+	assert_eq!(a.edge_multiplicity_to(&c), Some(1));
+	assert_eq!(a.edge_multiplicity_to(&a), Some(0));
+	let d = Node::new(Entity::new("d")).downgrade();
+	assert_eq!(d.edge_multiplicity_to(&a), None);
+}
+
+#[test]
+fn reap_isolated_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*d);
+	let (b_weak, c_weak) = (b.downgrade(), c.downgrade());
+	drop((b, c));
+	b_weak.lock().unwrap().preds.clear();
+	c_weak.lock().unwrap().preds.clear();
+	d.lock().preds.clear();
+	assert_eq!(a.reap_isolated_outgoing(), 2);
+	assert!(b_weak.upgrade().is_none());
+	assert!(c_weak.upgrade().is_none());
+	assert!(d.downgrade().upgrade().is_some());
+	assert!(a
+		.bfs_outgoing()
+		.filter(|node| node.upgrade().is_some())
+		.eq([a.downgrade(), d.downgrade()]));
+}
+
+#[test]
+fn lca_incoming() {
+	let [a, b, c, d, e, x] =
+		["a", "b", "c", "d", "e", "x"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&b, &d);
+	Entity::add_edge(&b, &e);
+	assert!(d.lca_incoming(&e) == Some(b.downgrade()));
+	assert!(d.lca_incoming(&c) == Some(a.downgrade()));
+	assert!(b.lca_incoming(&e) == Some(b.downgrade()));
+	assert!(d.lca_incoming(&x).is_none());
+}
+
+#[test]
+fn with_outgoing_slice() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	Entity::add_edge(&*a, &*b);
+	assert_eq!(a.with_outgoing_slice(|nodes| nodes == [b.downgrade()]), Some(true));
+	assert_eq!(b.with_incoming_slice(|nodes| nodes == [a.downgrade()]), Some(true));
+	assert_eq!(a.with_incoming_slice(<[_]>::len), Some(0));
+	let c = Node::new(Entity::new("c")).downgrade();
+	assert_eq!(c.with_outgoing_slice(<[_]>::len), None);
+}
+
+#[test]
+fn for_each_value_mut_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*a);
+	Entity::add_edge(&*c, &*a);
+	let mut count = 0;
+	a.for_each_value_mut_outgoing(|value| {
+		value.value = "x";
+		count += 1;
+	});
+	assert_eq!(count, 2);
+	assert_eq!(a.lock().value, "x");
+	assert_eq!(b.lock().value, "x");
+	assert_eq!(c.lock().value, "c");
+}
+
+#[test]
+fn shortest_path_tree_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*c, &*d);
+	Entity::add_edge(&*a, &*c);
+	let parents = a.shortest_path_tree_outgoing();
+	assert_eq!(parents.len(), 3);
+	assert!(parents[&b] == a.downgrade());
+	assert!(parents[&c] == a.downgrade());
+	assert!(parents[&d] == c.downgrade());
+}
+
+#[test]
+fn is_bipartite_undirected() {
+	let even = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	for i in 0..even.len() {
+		Entity::add_edge(&even[i], &even[(i + 1) % even.len()]);
+	}
+	assert!(even[0].is_bipartite_undirected());
+	let odd = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	for i in 0..odd.len() {
+		Entity::add_edge(&odd[i], &odd[(i + 1) % odd.len()]);
+	}
+	assert!(!odd[0].is_bipartite_undirected());
+	let a = Node::new(Entity::new("a"));
+	assert!(a.is_bipartite_undirected());
+	Entity::add_edge(&a, &a);
+	assert!(!a.is_bipartite_undirected());
+}
+
+#[test]
+fn outgoing_chunks() {
+	let a = Node::new(Entity::new("a"));
+	let nodes = ["b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	for node in &nodes {
+		Entity::add_edge(&a, node);
+	}
+	let chunks = a.outgoing_chunks(2);
+	assert!(
+		chunks == [vec![nodes[0].downgrade(), nodes[1].downgrade()], vec![nodes[2].downgrade()]]
+	);
+	assert_eq!(a.outgoing_chunks(3).len(), 1);
+	assert!(nodes[0].outgoing_chunks(2).is_empty());
+}
+
+#[test]
+fn degree_centrality_undirected() {
+	let a = Node::new(Entity::new("a"));
+	assert!(a.degree_centrality_undirected() == HashMap::from([(a.downgrade(), 0.0)]));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*a);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*c, &*c);
+	let centrality = c.degree_centrality_undirected();
+	assert_eq!(centrality.len(), 3);
+	assert_eq!(centrality[&a], 1.0);
+	assert_eq!(centrality[&b], 0.5);
+	assert_eq!(centrality[&c], 0.5);
+}
+
+#[test]
+fn simplify_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*b, &*c);
+	Entity::add_edge(&*b, &*c);
+	assert!(a.degree_histogram_outgoing() == std::collections::BTreeMap::from([(0, 1), (3, 2)]));
+	a.simplify_outgoing();
+	assert!(
+		a.degree_histogram_outgoing() == std::collections::BTreeMap::from([(0, 1), (1, 1), (2, 1)])
+	);
+	assert!(a.outgoing_snapshot().unwrap() == [b.downgrade(), c.downgrade()]);
+}
+
+#[test]
+fn summary_outgoing() {
+	let a = Node::new(Entity::new("a"));
+	let b = Node::new(Entity::new("b"));
+	let c = Node::new(Entity::new("c"));
+	let d = Node::new(Entity::new("d"));
+	Entity::add_edge(&*a, &*b);
+	Entity::add_edge(&*a, &*c);
+	Entity::add_edge(&*a, &*d);
+	Entity::add_edge(&*b, &*d);
+	let summary = a.summary_outgoing();
+	assert_eq!(summary.node_count, 4);
+	assert_eq!(summary.edge_count, 4);
+	assert_eq!(summary.source_count, 1);
+	assert_eq!(summary.sink_count, 2);
+	assert!(!summary.has_cycle);
+	assert_eq!(summary.max_out_degree, 3);
+	Entity::add_edge(&*d, &*a);
+	let summary = a.summary_outgoing();
+	assert_eq!((summary.source_count, summary.sink_count), (0, 1));
+	assert!(summary.has_cycle);
+}
+
+#[test]
+fn follow_first_outgoing_until() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &d);
+	Entity::add_edge(&b, &c);
+	assert!(
+		a.follow_first_outgoing_until(|value| value.value == "b") == [a.downgrade(), b.downgrade()]
+	);
+	assert!(
+		a.follow_first_outgoing_until(|_| false) == [a.downgrade(), b.downgrade(), c.downgrade()]
+	);
+	Entity::add_edge(&c, &a);
+	assert!(
+		b.follow_first_outgoing_until(|_| false) == [b.downgrade(), c.downgrade(), a.downgrade()]
+	);
+}
+
+#[test]
+fn replace_outgoing_target() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	a.connect(&b);
+	a.connect(&c);
+	assert!(a.replace_outgoing_target(&b, d.downgrade()));
+	assert!(a.outgoing_snapshot().unwrap() == [d.downgrade(), c.downgrade()]);
+	assert!(b.incoming_snapshot().unwrap().is_empty());
+	assert!(d.incoming_snapshot().unwrap() == [a.downgrade()]);
+	assert!(!a.replace_outgoing_target(&b, d.downgrade()));
+	assert!(a.replace_outgoing_target(&c, a.downgrade()));
+	assert!(a.outgoing_snapshot().unwrap() == [d.downgrade(), a.downgrade()]);
+	assert!(a.incoming_snapshot().unwrap() == [a.downgrade()]);
+}
+
+#[test]
+fn dfs_outgoing_stable() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &d);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&c, &b);
+	let order = a.dfs_outgoing_stable(|value| value.value).collect::<Vec<_>>();
+	assert!(order == [a.downgrade(), b.downgrade(), c.downgrade(), d.downgrade()]);
+	let order = a.dfs_outgoing_stable(|value| std::cmp::Reverse(value.value)).collect::<Vec<_>>();
+	assert!(order == [a.downgrade(), d.downgrade(), c.downgrade(), b.downgrade()]);
+}
+
+#[test]
+fn try_for_each_outgoing() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&a, &d);
+	let mut visited = Vec::new();
+	let result = a.try_for_each_outgoing(|node| {
+		let value = node.lock().unwrap().value;
+		node.lock().unwrap().succs.clear();
+		visited.push(value);
+		if value == "c" {
+			Err(value)
+		} else {
+			Ok(())
+		}
+	});
+	assert_eq!(result, Err("c"));
+	assert_eq!(visited, ["b", "c"]);
+	assert_eq!(a.try_for_each_outgoing(|_| Ok::<_, ()>(())), Ok(()));
+}
+
+#[test]
+fn enumerate_outgoing() {
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&b, &d);
+	Entity::add_edge(&c, &d);
+	Entity::add_edge(&d, &a);
+	let indices = b.enumerate_outgoing();
+	assert_eq!(indices.len(), 4);
+	assert_eq!(indices[&b.downgrade()], 0);
+	assert_eq!(indices[&d.downgrade()], 1);
+	assert_eq!(indices[&a.downgrade()], 2);
+	assert_eq!(indices[&c.downgrade()], 3);
+}
+
+#[test]
+fn articulation_points_undirected() {
+	let [a, b, x, c, d] = ["a", "b", "x", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&b, &x);
+	Entity::add_edge(&x, &a);
+	Entity::add_edge(&x, &c);
+	Entity::add_edge(&c, &d);
+	Entity::add_edge(&d, &x);
+	assert!(a.articulation_points_undirected() == [x.downgrade()]);
+	assert!(x.articulation_points_undirected() == [x.downgrade()]);
+	Entity::add_edge(&b, &c);
+	assert!(a.articulation_points_undirected().is_empty());
+	let e = Node::new(Entity::new("e"));
+	Entity::add_edge(&e, &d);
+	assert!(e.articulation_points_undirected() == [d.downgrade()]);
+}
+
+#[test]
+fn bridges_undirected() {
+	let [a, b, c, d, e, f] =
+		["a", "b", "c", "d", "e", "f"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&b, &c);
+	Entity::add_edge(&c, &a);
+	Entity::add_edge(&d, &e);
+	Entity::add_edge(&e, &f);
+	Entity::add_edge(&f, &d);
+	Entity::add_edge(&d, &c);
+	let bridges = a.bridges_undirected();
+	assert_eq!(bridges.len(), 1);
+	let (from, to) = &bridges[0];
+	assert!(from < to);
+	assert!(
+		HashSet::from([from.clone(), to.clone()]) == HashSet::from([c.downgrade(), d.downgrade()])
+	);
+	Entity::add_edge(&c, &d);
+	assert_eq!(a.bridges_undirected().len(), 1);
+	Entity::add_edge(&d, &c);
+	assert_eq!(a.bridges_undirected().len(), 1);
+	Entity::add_edge(&e, &b);
+	assert!(f.bridges_undirected().is_empty());
+}
+
+#[test]
+fn outgoing_first_last() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	assert!(a.outgoing_first().is_none());
+	assert!(a.outgoing_last().is_none());
+	Entity::add_edge(&a, &b);
+	assert!(a.outgoing_first() == Some(b.downgrade()));
+	assert!(a.outgoing_last() == Some(b.downgrade()));
+	Entity::add_edge(&a, &c);
+	assert!(a.outgoing_first() == Some(b.downgrade()));
+	assert!(a.outgoing_last() == Some(c.downgrade()));
+	let d = {
+		let [d, e] = ["d", "e"].map(|value| Node::new(Entity::new(value)));
+		Entity::add_edge(&d, &e);
+		d.downgrade()
+	};
+	assert!(d.outgoing_first().is_none());
+}
+
+#[test]
+fn structurally_eq_outgoing() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	let [x, y, z] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	for (from, to) in [(&a, &b), (&a, &c), (&b, &c), (&c, &a)] {
+		Entity::add_edge(from, to);
+	}
+	for (from, to) in [(&x, &y), (&x, &z), (&y, &z)] {
+		Entity::add_edge(from, to);
+	}
+	assert!(a.structurally_eq_outgoing(&a));
+	assert!(!a.structurally_eq_outgoing(&x));
+	Entity::add_edge(&z, &x);
+	assert!(a.structurally_eq_outgoing(&x));
+	assert!(!b.structurally_eq_outgoing(&x));
+	z.lock().value = "d";
+	assert!(!a.structurally_eq_outgoing(&x));
+}
+
+#[test]
+fn update() {
+	let [a, b] = ["a", "b"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	let old = a.downgrade().update(|entity| (Entity { value: "c", ..entity }, entity.value));
+	assert_eq!(old, Some("a"));
+	assert_eq!(a.lock().value, "c");
+	assert!(a.outgoing().eq([b.downgrade()]));
+	let c = {
+		let c = Node::new(Entity::new("c"));
+		c.downgrade()
+	};
+	assert_eq!(c.update(|entity| (entity, ())), None);
+}
+
+#[test]
+fn explorer_outgoing() {
+	let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&b, &d);
+	Entity::add_edge(&d, &a);
+	let mut explorer = a.explorer_outgoing();
+	assert!(explorer.next_batch(2) == [a.downgrade(), b.downgrade()]);
+	Entity::add_edge(&c, &e);
+	assert!(explorer.next_batch(2) == [c.downgrade(), d.downgrade()]);
+	assert!(explorer.next_batch(2) == [e.downgrade()]);
+	assert!(explorer.next_batch(2).is_empty());
+}
+
+#[test]
+fn topological_outgoing() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&b, &c);
+	assert!(a.topological_outgoing().unwrap() == [c.downgrade(), b.downgrade(), a.downgrade()]);
+	assert!(b.topological_outgoing().unwrap() == [c.downgrade(), b.downgrade()]);
+	Entity::add_edge(&c, &a);
+	let error = a.topological_outgoing().unwrap_err();
+	assert!([a.downgrade(), b.downgrade(), c.downgrade()].contains(error.node()));
+}
+
+#[test]
+fn has_cycle() {
+	let [a, b, c] = ["a", "b", "c"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&b, &c);
+	Entity::add_edge(&c, &a);
+	assert!(a.has_cycle_outgoing());
+	assert!(b.has_cycle_incoming());
+	let [a, b, c, d] = ["a", "b", "c", "d"].map(|value| Node::new(Entity::new(value)));
+	Entity::add_edge(&a, &b);
+	Entity::add_edge(&a, &c);
+	Entity::add_edge(&b, &d);
+	Entity::add_edge(&c, &d);
+	assert!(!a.has_cycle_outgoing());
+	assert!(!d.has_cycle_incoming());
+	Entity::add_edge(&d, &a);
+	assert!(a.has_cycle_outgoing());
+	assert!(d.has_cycle_incoming());
 }
\ No newline at end of file